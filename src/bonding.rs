@@ -0,0 +1,63 @@
+use crate::{
+    data_management::{get_bond_vesting, get_user_bond, remove_user_bond, set_user_bond},
+    seven_seas::{mint_token, BondPosition, DataKey},
+};
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+
+pub fn is_bond_market(e: &Env, voyage_id: i32) -> bool {
+    e.data().has(DataKey::BondVest(voyage_id))
+}
+
+// doubloons that have vested since the position was last claimed - payouts vest linearly over the remaining vesting period
+pub fn vested_amount(e: &Env, position: &BondPosition) -> BigInt {
+    let elapsed = e.ledger().sequence() - position.last_ldgr;
+    if elapsed >= position.vesting {
+        return position.payout.clone();
+    }
+    position.payout.clone() * BigInt::from_u32(&e, elapsed) / BigInt::from_u32(&e, position.vesting)
+}
+
+// mints the vested doubloons from a user's bond position and returns the amount claimed
+pub fn claim_bond(e: &Env, user: Identifier, voyage_id: i32) -> BigInt {
+    let position = get_user_bond(&e, user.clone(), voyage_id);
+    let vested = vested_amount(&e, &position);
+    let block_now = e.ledger().sequence();
+    let elapsed = block_now - position.last_ldgr;
+    let remaining = position.payout - vested.clone();
+    if remaining == BigInt::zero(&e) {
+        remove_user_bond(&e, user.clone(), voyage_id);
+    } else {
+        set_user_bond(
+            &e,
+            user.clone(),
+            voyage_id,
+            BondPosition {
+                payout: remaining,
+                vesting: position.vesting - elapsed,
+                last_ldgr: block_now,
+            },
+        );
+    }
+    if vested > BigInt::zero(&e) {
+        mint_token(&e, user, vested.clone());
+    }
+    vested
+}
+
+// adds a payout to a user's bond position, claiming what has already vested and restarting the vesting period, returns the amount claimed
+pub fn add_bond(e: &Env, user: Identifier, voyage_id: i32, payout: BigInt) -> BigInt {
+    let claimed = claim_bond(&e, user.clone(), voyage_id);
+    let position = get_user_bond(&e, user.clone(), voyage_id);
+    set_user_bond(
+        &e,
+        user,
+        voyage_id,
+        BondPosition {
+            payout: position.payout + payout,
+            vesting: get_bond_vesting(&e, voyage_id),
+            last_ldgr: e.ledger().sequence(),
+        },
+    );
+    claimed
+}
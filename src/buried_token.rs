@@ -0,0 +1,122 @@
+//! sDOUBLOON - buried doubloons exposed through the standard token interface
+//!
+//! Buried positions are stored as shares of the rebase index. Token balances and amounts are in
+//! doubloon terms, converted with the current index, so balances grow as the index rebases.
+//!
+//! wsDOUBLOON is a standard token minted by the sea that holds buried shares at a fixed balance,
+//! one wrapped token per share, so its value in doubloons rises with the index instead.
+
+use crate::{
+    data_management::{
+        get_allowance, get_new_index, get_nonce, get_user_buried, get_wrapped_client, has_wrapped,
+        set_allowance, set_nonce, set_user_buried, SCALER,
+    },
+    events::user_event,
+    seven_seas::SevenSeasError,
+};
+use soroban_auth::{Identifier, Signature};
+use soroban_sdk::{symbol, BigInt, Env};
+
+// converts buried shares to doubloons at an index, every entry point and preview converts through this
+pub fn doubloons_at(e: &Env, shares: BigInt, index: BigInt) -> BigInt {
+    shares * index / BigInt::from_i64(&e, SCALER * SCALER)
+}
+
+// converts doubloons to buried shares at an index, every entry point and preview converts through this
+pub fn shares_at(e: &Env, amount: BigInt, index: BigInt) -> BigInt {
+    amount * BigInt::from_i64(&e, SCALER * SCALER) / index
+}
+
+pub fn to_doubloons(e: &Env, shares: BigInt) -> BigInt {
+    doubloons_at(&e, shares, get_new_index(&e))
+}
+
+pub fn to_shares(e: &Env, amount: BigInt) -> BigInt {
+    shares_at(&e, amount, get_new_index(&e))
+}
+
+pub fn verify_and_consume_nonce(
+    e: &Env,
+    auth: &Signature,
+    expected_nonce: &BigInt,
+) -> Result<(), SevenSeasError> {
+    match auth {
+        Signature::Invoker => {
+            if BigInt::zero(&e) != expected_nonce {
+                return Err(SevenSeasError::InvalidNonce);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let id = auth.identifier(&e);
+    let nonce = get_nonce(&e, id.clone());
+    if nonce != expected_nonce {
+        return Err(SevenSeasError::InvalidNonce);
+    }
+    set_nonce(&e, id, nonce + BigInt::from_u32(&e, 1));
+    Ok(())
+}
+
+pub fn spend_allowance(
+    e: &Env,
+    from: Identifier,
+    spender: Identifier,
+    amount: BigInt,
+) -> Result<(), SevenSeasError> {
+    let allowance = get_allowance(&e, from.clone(), spender.clone());
+    if allowance < amount {
+        return Err(SevenSeasError::InsufficientAllowance);
+    }
+    set_allowance(&e, from, spender, allowance - amount);
+    Ok(())
+}
+
+// moves buried doubloons between users, the amount is in doubloon terms
+pub fn xfer_buried(
+    e: &Env,
+    from: Identifier,
+    to: Identifier,
+    amount: BigInt,
+) -> Result<(), SevenSeasError> {
+    if amount < BigInt::zero(&e) {
+        return Err(SevenSeasError::NegativeAmount);
+    }
+    let shares = to_shares(&e, amount.clone());
+    let from_shares = get_user_buried(&e, from.clone());
+    if from_shares < shares {
+        return Err(SevenSeasError::NotEnoughBuried);
+    }
+    set_user_buried(&e, from.clone(), from_shares - shares.clone());
+    let to_shares = get_user_buried(&e, to.clone());
+    set_user_buried(&e, to.clone(), to_shares + shares.clone());
+    user_event(&e, symbol!("xfer"), from, (to, amount, shares));
+    Ok(())
+}
+
+// moves a user's buried shares into wrapped tokens, the amount is in doubloon terms
+pub fn wrap_buried(e: &Env, user: Identifier, amount: BigInt) -> Result<BigInt, SevenSeasError> {
+    if !has_wrapped(&e) {
+        return Err(SevenSeasError::NoWrappedToken);
+    }
+    let shares = to_shares(&e, amount);
+    let user_shares = get_user_buried(&e, user.clone());
+    if user_shares < shares {
+        return Err(SevenSeasError::NotEnoughBuried);
+    }
+    set_user_buried(&e, user.clone(), user_shares - shares.clone());
+    get_wrapped_client(&e).mint(&Signature::Invoker, &BigInt::zero(&e), &user, &shares);
+    Ok(shares)
+}
+
+// moves wrapped tokens back into a user's buried shares, returning their value in doubloons
+pub fn unwrap_buried(e: &Env, user: Identifier, wrapped: BigInt) -> Result<BigInt, SevenSeasError> {
+    if !has_wrapped(&e) {
+        return Err(SevenSeasError::NoWrappedToken);
+    }
+    get_wrapped_client(&e).burn(&Signature::Invoker, &BigInt::zero(&e), &user, &wrapped);
+    let user_shares = get_user_buried(&e, user.clone());
+    set_user_buried(&e, user, user_shares + wrapped.clone());
+    Ok(to_doubloons(&e, wrapped))
+}
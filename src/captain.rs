@@ -1,8 +1,11 @@
-use crate::seven_seas::DataKey;
+use crate::seven_seas::{
+    ActionProp, AdminAction, CapnProposal, Council, DataKey, Role, RoleKey, SevenSeasError,
+    HANDOVER_WINDOW,
+};
 use soroban_auth::{Identifier, Signature};
-use soroban_sdk::Env;
+use soroban_sdk::{vec, Env, Vec};
 
-fn read_captain(e: &Env) -> Identifier {
+pub fn read_captain(e: &Env) -> Identifier {
     let key = DataKey::Admin;
     e.data().get_unchecked(key).unwrap()
 }
@@ -12,9 +15,138 @@ pub fn write_captain(e: &Env, id: Identifier) {
     e.data().set(key, id);
 }
 
-pub fn check_captain(e: &Env, auth: &Signature) {
+pub fn check_captain(e: &Env, auth: &Signature) -> Result<(), SevenSeasError> {
     let auth_id = auth.identifier(&e);
     if auth_id != read_captain(&e) {
-        panic!("not authorized by captain")
+        return Err(SevenSeasError::NotCaptain);
     }
+    Ok(())
+}
+
+pub fn read_proposal(e: &Env) -> Result<CapnProposal, SevenSeasError> {
+    let key = DataKey::CapnProp;
+    if !e.data().has(key.clone()) {
+        return Err(SevenSeasError::NoProposal);
+    }
+    Ok(e.data().get_unchecked(key).unwrap())
+}
+
+// proposes a new captain and returns the last block the proposal can be accepted on
+pub fn write_proposal(e: &Env, nominee: Identifier) -> u32 {
+    let expires = e.ledger().sequence() + HANDOVER_WINDOW;
+    e.data()
+        .set(DataKey::CapnProp, CapnProposal { nominee, expires });
+    expires
+}
+
+pub fn remove_proposal(e: &Env) {
+    e.data().remove(DataKey::CapnProp);
+}
+
+pub fn has_role(e: &Env, role: Role, id: Identifier) -> bool {
+    e.data().has(DataKey::Role(RoleKey { role, id }))
+}
+
+// returns every role held, by role and identity
+pub fn read_roles(e: &Env) -> Vec<RoleKey> {
+    e.data()
+        .get(DataKey::Roles)
+        .unwrap_or(Ok(vec![&e]))
+        .unwrap()
+}
+
+pub fn write_role(e: &Env, role: Role, id: Identifier) {
+    let key = RoleKey { role, id };
+    let mut roles = read_roles(&e);
+    if !roles.contains(&key) {
+        roles.push_back(key.clone());
+        e.data().set(DataKey::Roles, roles);
+    }
+    e.data().set(DataKey::Role(key), true);
+}
+
+pub fn remove_role(e: &Env, role: Role, id: Identifier) {
+    let key = RoleKey { role, id };
+    let mut roles = read_roles(&e);
+    if let Some(i) = roles.first_index_of(&key) {
+        roles.remove(i);
+        e.data().set(DataKey::Roles, roles);
+    }
+    e.data().remove(DataKey::Role(key));
+}
+
+pub fn check_role(e: &Env, role: Role, auth: &Signature) -> Result<(), SevenSeasError> {
+    if !has_role(&e, role, auth.identifier(&e)) {
+        return Err(SevenSeasError::MissingRole);
+    }
+    Ok(())
+}
+
+pub fn read_council(e: &Env) -> Result<Council, SevenSeasError> {
+    let key = DataKey::Council;
+    if !e.data().has(key.clone()) {
+        return Err(SevenSeasError::NotCouncil);
+    }
+    Ok(e.data().get_unchecked(key).unwrap())
+}
+
+pub fn write_council(e: &Env, council: Council) {
+    e.data().set(DataKey::Council, council);
+}
+
+pub fn remove_council(e: &Env) {
+    e.data().remove(DataKey::Council);
+}
+
+// checks that the council is the captain and the invoker is on it, then returns the council
+pub fn check_member(e: &Env, auth: &Signature) -> Result<Council, SevenSeasError> {
+    let council = read_council(&e)?;
+    if !council.members.contains(&auth.identifier(&e)) {
+        return Err(SevenSeasError::NotMember);
+    }
+    Ok(council)
+}
+
+pub fn read_action(e: &Env, action_id: u32) -> Result<ActionProp, SevenSeasError> {
+    let key = DataKey::Action(action_id);
+    if !e.data().has(key.clone()) {
+        return Err(SevenSeasError::NoAction);
+    }
+    Ok(e.data().get_unchecked(key).unwrap())
+}
+
+// proposes an admin action approved by the proposer and returns its id and the last block it can be executed on
+pub fn write_action(e: &Env, proposer: Identifier, action: AdminAction) -> (u32, u32) {
+    let action_id: u32 = e.data().get(DataKey::LastAction).unwrap_or(Ok(0)).unwrap() + 1;
+    let expires = e.ledger().sequence() + HANDOVER_WINDOW;
+    e.data().set(DataKey::LastAction, action_id);
+    set_action(
+        &e,
+        action_id,
+        ActionProp {
+            action,
+            approvals: vec![&e, proposer],
+            expires,
+        },
+    );
+    (action_id, expires)
+}
+
+pub fn set_action(e: &Env, action_id: u32, proposal: ActionProp) {
+    e.data().set(DataKey::Action(action_id), proposal);
+}
+
+pub fn remove_action(e: &Env, action_id: u32) {
+    e.data().remove(DataKey::Action(action_id));
+}
+
+// counts the approvals from addresses still on the council
+pub fn count_approvals(council: &Council, proposal: &ActionProp) -> u32 {
+    let mut count = 0;
+    for approver in proposal.approvals.iter() {
+        if council.members.contains(&approver.unwrap()) {
+            count += 1;
+        }
+    }
+    count
 }
@@ -1,8 +1,12 @@
 use crate::{
-    seven_seas::{DataKey, VoyageInfo, VoyageKey},
+    rebase::{project_index, EPOCH_LEN},
+    seven_seas::{
+        AllowanceKey, BondPosition, DataKey, LockPosition, PriceCtrl, PriceMode, QueueEntry,
+        RaidCommit, RaidRequest, SevenSeasError, VoyageInfo, VoyageInfoV1, VoyageKey,
+    },
     token::Identifier,
 };
-use soroban_sdk::{BigInt, BytesN, Env};
+use soroban_sdk::{BigInt, BytesN, Env, Vec};
 
 pub const SCALER: i64 = 10000000;
 
@@ -22,27 +26,40 @@ pub fn get_rate(e: &Env) -> BigInt {
     e.data().get_unchecked(DataKey::Rate).unwrap()
 }
 
-pub fn get_decimals(e: &Env) -> BigInt {
-    e.data().get_unchecked(DataKey::Decimals).unwrap()
+pub fn get_decimals(e: &Env) -> u32 {
+    let decimals: BigInt = e.data().get_unchecked(DataKey::Decimals).unwrap();
+    decimals.to_u32()
 }
 pub fn get_index(e: &Env) -> BigInt {
     e.data().get_unchecked(DataKey::Index).unwrap()
 }
 
 pub fn get_new_index(e: &Env) -> BigInt {
-    let block_now = e.ledger().sequence();
-    //multiply by 1000 to avoid decimals
-    return get_index(e)
-        + get_rate(e)
-            * BigInt::from_u32(&e, block_now - get_last_block(e))
-            * BigInt::from_i64(e, SCALER)
-            / 100;
+    project_index(e)
 }
 
 pub fn get_last_block(e: &Env) -> u32 {
     e.data().get_unchecked(DataKey::LastBlock).unwrap()
 }
 
+pub fn get_epoch_len(e: &Env) -> u32 {
+    e.data()
+        .get(DataKey::EpochLen)
+        .unwrap_or(Ok(EPOCH_LEN))
+        .unwrap()
+}
+
+pub fn get_epoch_num(e: &Env) -> u32 {
+    e.data().get(DataKey::EpochNum).unwrap_or(Ok(0)).unwrap()
+}
+
+pub fn get_bounty(e: &Env) -> BigInt {
+    e.data()
+        .get(DataKey::Bounty)
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
 pub fn get_base_token(e: &Env) -> BytesN<32> {
     e.data().get_unchecked(DataKey::BaseToken).unwrap()
 }
@@ -52,14 +69,45 @@ pub fn get_base_token_client(e: &Env) -> crate::token::Client {
     crate::token::Client::new(e, id)
 }
 
-pub fn get_voyage(e: &Env, voyage_id: i32) -> VoyageInfo {
-    e.data().get_unchecked(DataKey::Voyages(voyage_id)).unwrap()
+pub fn get_voyage(e: &Env, voyage_id: i32) -> Result<VoyageInfo, SevenSeasError> {
+    let key = DataKey::Voyages(voyage_id);
+    if !e.data().has(key.clone()) {
+        return Err(SevenSeasError::NoVoyage);
+    }
+    let voyage: Result<VoyageInfo, _> = e.data().get_unchecked(key.clone());
+    Ok(match voyage {
+        Ok(voyage) => voyage,
+        // voyages created before offerings had schedules could be embarked on until their expiration at a fixed price and paid one doubloon
+        Err(_) => {
+            let voyage: VoyageInfoV1 = e.data().get_unchecked(key).unwrap();
+            VoyageInfo {
+                vyg_asset: voyage.vyg_asset,
+                price: voyage.price,
+                max_vygs: voyage.max_vygs,
+                n_embarked: voyage.n_embarked,
+                reward: BigInt::from_i64(&e, SCALER),
+                expiration: voyage.expiration,
+                start_ldgr: 0,
+                embark_dl: voyage.expiration,
+                sail_dur: 0,
+                price_mode: PriceMode::Fixed,
+            }
+        }
+    })
 }
 
 pub fn get_last_voyage_id(e: &Env) -> i32 {
     e.data().get(DataKey::LastVoyage).unwrap_or(Ok(0)).unwrap()
 }
 
+// ids of the voyages a user has an open position on
+pub fn get_user_voyage_ids(e: &Env, user: Identifier) -> Vec<i32> {
+    e.data()
+        .get(DataKey::UserVygs(user))
+        .unwrap_or(Ok(Vec::new(&e)))
+        .unwrap()
+}
+
 pub fn get_user_voyage(e: &Env, user: Identifier, voyage: i32) -> BigInt {
     let voyage_key = VoyageKey {
         user_id: user.clone(),
@@ -73,6 +121,172 @@ pub fn get_user_voyage(e: &Env, user: Identifier, voyage: i32) -> BigInt {
     return data;
 }
 
+pub fn get_voyage_users(e: &Env, voyage_id: i32) -> Vec<Identifier> {
+    e.data()
+        .get(DataKey::VygUsers(voyage_id))
+        .unwrap_or(Ok(Vec::new(&e)))
+        .unwrap()
+}
+
+// offerings created before positions were tracked don't know every user on them until they are repaired
+pub fn is_voyage_tracked(e: &Env, voyage_id: i32) -> bool {
+    e.data().has(DataKey::VygTrack(voyage_id))
+}
+
+pub fn get_voyage_closed(e: &Env, voyage_id: i32) -> BigInt {
+    e.data()
+        .get(DataKey::VygClosed(voyage_id))
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn get_exit_penalty(e: &Env) -> BigInt {
+    e.data()
+        .get(DataKey::ExitPen)
+        .unwrap_or(Ok(BigInt::from_i64(&e, SCALER)))
+        .unwrap()
+}
+
+pub fn get_bond_vesting(e: &Env, voyage_id: i32) -> u32 {
+    e.data()
+        .get_unchecked(DataKey::BondVest(voyage_id))
+        .unwrap()
+}
+
+pub fn has_user_bond(e: &Env, user: Identifier, voyage_id: i32) -> bool {
+    let voyage_key = VoyageKey {
+        user_id: user,
+        voyage_id: voyage_id,
+    };
+    e.data().has(DataKey::UserBond(voyage_key))
+}
+
+pub fn get_user_bond(e: &Env, user: Identifier, voyage_id: i32) -> BondPosition {
+    let voyage_key = VoyageKey {
+        user_id: user,
+        voyage_id: voyage_id,
+    };
+    e.data()
+        .get(DataKey::UserBond(voyage_key))
+        .unwrap_or(Ok(BondPosition {
+            payout: BigInt::zero(&e),
+            vesting: 0,
+            last_ldgr: e.ledger().sequence(),
+        }))
+        .unwrap()
+}
+
+pub fn has_price_ctrl(e: &Env, voyage_id: i32) -> bool {
+    e.data().has(DataKey::PriceCtrl(voyage_id))
+}
+
+pub fn get_price_ctrl(e: &Env, voyage_id: i32) -> PriceCtrl {
+    e.data()
+        .get_unchecked(DataKey::PriceCtrl(voyage_id))
+        .unwrap()
+}
+
+pub fn get_supply(e: &Env) -> BigInt {
+    e.data()
+        .get(DataKey::Supply)
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn get_paused(e: &Env) -> bool {
+    e.data().get(DataKey::Paused).unwrap_or(Ok(false)).unwrap()
+}
+
+pub fn get_allowance(e: &Env, from: Identifier, spender: Identifier) -> BigInt {
+    let key = AllowanceKey { from, spender };
+    e.data()
+        .get(DataKey::Allowance(key))
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn get_nonce(e: &Env, id: Identifier) -> BigInt {
+    e.data()
+        .get(DataKey::Nonce(id))
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn has_reserve(e: &Env, asset: BytesN<32>) -> bool {
+    e.data().has(DataKey::Reserve(asset))
+}
+
+pub fn get_reserve(e: &Env, asset: BytesN<32>) -> BigInt {
+    e.data()
+        .get(DataKey::Reserve(asset))
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn get_reserve_assets(e: &Env) -> Vec<BytesN<32>> {
+    e.data()
+        .get(DataKey::RsvAssets)
+        .unwrap_or(Ok(Vec::new(&e)))
+        .unwrap()
+}
+
+pub fn get_rfv(e: &Env, asset: BytesN<32>) -> BigInt {
+    e.data()
+        .get(DataKey::Rfv(asset))
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn get_reward_cap(e: &Env) -> BigInt {
+    e.data()
+        .get(DataKey::RwdCap)
+        .unwrap_or(Ok(BigInt::from_i64(&e, SCALER)))
+        .unwrap()
+}
+
+pub fn get_warmup(e: &Env) -> u32 {
+    e.data().get(DataKey::Warmup).unwrap_or(Ok(0)).unwrap()
+}
+
+pub fn get_cooldown(e: &Env) -> u32 {
+    e.data().get(DataKey::Cooldown).unwrap_or(Ok(0)).unwrap()
+}
+
+pub fn get_user_warmup(e: &Env, user: Identifier) -> Vec<QueueEntry> {
+    e.data()
+        .get(DataKey::UserWarm(user))
+        .unwrap_or(Ok(Vec::new(&e)))
+        .unwrap()
+}
+
+pub fn get_user_cooldown(e: &Env, user: Identifier) -> Vec<QueueEntry> {
+    e.data()
+        .get(DataKey::UserCool(user))
+        .unwrap_or(Ok(Vec::new(&e)))
+        .unwrap()
+}
+
+pub fn get_total_queued(e: &Env) -> BigInt {
+    e.data()
+        .get(DataKey::TtlQueued)
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
+pub fn get_user_locks(e: &Env, user: Identifier) -> Vec<LockPosition> {
+    e.data()
+        .get(DataKey::UserLocks(user))
+        .unwrap_or(Ok(Vec::new(&e)))
+        .unwrap()
+}
+
+pub fn get_lock_offset(e: &Env) -> BigInt {
+    e.data()
+        .get(DataKey::LockOffset)
+        .unwrap_or(Ok(BigInt::zero(&e)))
+        .unwrap()
+}
+
 pub fn get_last_raid_block(e: &Env) -> u32 {
     e.data().get(DataKey::LastRaid).unwrap_or(Ok(0)).unwrap()
 }
@@ -81,6 +295,47 @@ pub fn get_target_raid_interval(e: &Env) -> u32 {
     e.data().get_unchecked(DataKey::TgtRaidInt).unwrap()
 }
 
+pub fn has_raid_commit(e: &Env, request_id: u32) -> bool {
+    e.data().has(DataKey::RaidCmt(request_id))
+}
+
+pub fn get_raid_commit(e: &Env, request_id: u32) -> RaidCommit {
+    e.data()
+        .get_unchecked(DataKey::RaidCmt(request_id))
+        .unwrap()
+}
+
+pub fn has_oracle(e: &Env) -> bool {
+    e.data().has(DataKey::Oracle)
+}
+
+pub fn get_oracle(e: &Env) -> BytesN<32> {
+    e.data().get_unchecked(DataKey::Oracle).unwrap()
+}
+
+pub fn has_wrapped(e: &Env) -> bool {
+    e.data().has(DataKey::Wrapped)
+}
+
+pub fn get_wrapped_client(e: &Env) -> crate::token::Client {
+    let id: BytesN<32> = e.data().get_unchecked(DataKey::Wrapped).unwrap();
+    crate::token::Client::new(e, id)
+}
+
+pub fn has_raid_request(e: &Env, request_id: u32) -> bool {
+    e.data().has(DataKey::RaidReq(request_id))
+}
+
+pub fn get_raid_request(e: &Env, request_id: u32) -> RaidRequest {
+    e.data()
+        .get_unchecked(DataKey::RaidReq(request_id))
+        .unwrap()
+}
+
+pub fn get_last_request_id(e: &Env) -> u32 {
+    e.data().get(DataKey::LastReqId).unwrap_or(Ok(0)).unwrap()
+}
+
 /******** Write Functions */
 pub fn set_user_buried(e: &Env, user_id: Identifier, amount: BigInt) {
     let key = DataKey::UserBuried(user_id.clone());
@@ -95,9 +350,20 @@ pub fn set_index(e: &Env, index: BigInt) {
     e.data().set(DataKey::Index, index)
 }
 
-pub fn set_last_block(e: &Env) {
-    let block_now = e.ledger().sequence();
-    e.data().set(DataKey::LastBlock, block_now)
+pub fn set_last_block(e: &Env, block: u32) {
+    e.data().set(DataKey::LastBlock, block)
+}
+
+pub fn set_epoch_len(e: &Env, epoch_len: u32) {
+    e.data().set(DataKey::EpochLen, epoch_len)
+}
+
+pub fn set_epoch_num(e: &Env, epoch_num: u32) {
+    e.data().set(DataKey::EpochNum, epoch_num)
+}
+
+pub fn set_bounty(e: &Env, bounty: BigInt) {
+    e.data().set(DataKey::Bounty, bounty)
 }
 
 pub fn set_decimals(e: &Env) {
@@ -121,18 +387,147 @@ pub fn set_user_voyage(e: &Env, user: Identifier, voyage_id: i32, amount: BigInt
         user_id: user.clone(),
         voyage_id: voyage_id,
     };
+    let mut ids = get_user_voyage_ids(&e, user.clone());
+    if !ids.contains(&voyage_id) {
+        ids.push_back(voyage_id);
+        e.data().set(DataKey::UserVygs(user.clone()), ids);
+    }
+    let mut users = get_voyage_users(&e, voyage_id);
+    if !users.contains(&user) {
+        users.push_back(user);
+        e.data().set(DataKey::VygUsers(voyage_id), users);
+    }
 
     e.data().set(DataKey::UserVoyage(voyage_key), amount)
 }
 
 pub fn remove_user_voyage(e: &Env, user: Identifier, voyage_id: i32) {
     let voyage_key = VoyageKey {
-        user_id: user,
+        user_id: user.clone(),
         voyage_id: voyage_id,
     };
+    let mut ids = get_user_voyage_ids(&e, user.clone());
+    if let Some(i) = ids.first_index_of(&voyage_id) {
+        ids.remove(i);
+        e.data().set(DataKey::UserVygs(user.clone()), ids);
+    }
+    let mut users = get_voyage_users(&e, voyage_id);
+    if let Some(i) = users.first_index_of(&user) {
+        users.remove(i);
+        e.data().set(DataKey::VygUsers(voyage_id), users);
+    }
     e.data().remove(DataKey::UserVoyage(voyage_key));
 }
 
+pub fn set_voyage_tracked(e: &Env, voyage_id: i32) {
+    e.data().set(DataKey::VygTrack(voyage_id), true)
+}
+
+pub fn set_voyage_closed(e: &Env, voyage_id: i32, amount: BigInt) {
+    e.data().set(DataKey::VygClosed(voyage_id), amount)
+}
+
+pub fn set_exit_penalty(e: &Env, penalty: BigInt) {
+    e.data().set(DataKey::ExitPen, penalty)
+}
+
+pub fn set_bond_vesting(e: &Env, voyage_id: i32, vest_len: u32) {
+    e.data().set(DataKey::BondVest(voyage_id), vest_len)
+}
+
+pub fn set_user_bond(e: &Env, user: Identifier, voyage_id: i32, position: BondPosition) {
+    let voyage_key = VoyageKey {
+        user_id: user,
+        voyage_id: voyage_id,
+    };
+    e.data().set(DataKey::UserBond(voyage_key), position)
+}
+
+pub fn remove_user_bond(e: &Env, user: Identifier, voyage_id: i32) {
+    let voyage_key = VoyageKey {
+        user_id: user,
+        voyage_id: voyage_id,
+    };
+    e.data().remove(DataKey::UserBond(voyage_key));
+}
+
+pub fn set_price_ctrl(e: &Env, voyage_id: i32, ctrl: PriceCtrl) {
+    e.data().set(DataKey::PriceCtrl(voyage_id), ctrl)
+}
+
+pub fn set_supply(e: &Env, supply: BigInt) {
+    e.data().set(DataKey::Supply, supply)
+}
+
+pub fn set_paused(e: &Env, paused: bool) {
+    e.data().set(DataKey::Paused, paused)
+}
+
+pub fn set_allowance(e: &Env, from: Identifier, spender: Identifier, amount: BigInt) {
+    let key = AllowanceKey { from, spender };
+    e.data().set(DataKey::Allowance(key), amount)
+}
+
+pub fn set_nonce(e: &Env, id: Identifier, nonce: BigInt) {
+    e.data().set(DataKey::Nonce(id), nonce)
+}
+
+pub fn set_reserve(e: &Env, asset: BytesN<32>, amount: BigInt) {
+    e.data().set(DataKey::Reserve(asset), amount)
+}
+
+pub fn set_reserve_assets(e: &Env, assets: Vec<BytesN<32>>) {
+    e.data().set(DataKey::RsvAssets, assets)
+}
+
+pub fn set_rfv(e: &Env, asset: BytesN<32>, value: BigInt) {
+    e.data().set(DataKey::Rfv(asset), value)
+}
+
+pub fn set_reward_cap(e: &Env, cap: BigInt) {
+    e.data().set(DataKey::RwdCap, cap)
+}
+
+pub fn set_warmup(e: &Env, blocks: u32) {
+    e.data().set(DataKey::Warmup, blocks)
+}
+
+pub fn set_cooldown(e: &Env, blocks: u32) {
+    e.data().set(DataKey::Cooldown, blocks)
+}
+
+pub fn set_user_warmup(e: &Env, user: Identifier, queue: Vec<QueueEntry>) {
+    if queue.is_empty() {
+        e.data().remove(DataKey::UserWarm(user))
+    } else {
+        e.data().set(DataKey::UserWarm(user), queue)
+    }
+}
+
+pub fn set_user_cooldown(e: &Env, user: Identifier, queue: Vec<QueueEntry>) {
+    if queue.is_empty() {
+        e.data().remove(DataKey::UserCool(user))
+    } else {
+        e.data().set(DataKey::UserCool(user), queue)
+    }
+}
+
+pub fn set_total_queued(e: &Env, amount: BigInt) {
+    e.data().set(DataKey::TtlQueued, amount)
+}
+
+pub fn set_user_locks(e: &Env, user: Identifier, locks: Vec<LockPosition>) {
+    if locks.is_empty() {
+        e.data().remove(DataKey::UserLocks(user))
+    } else {
+        e.data().set(DataKey::UserLocks(user), locks)
+    }
+}
+
+pub fn set_lock_offset(e: &Env, offset: BigInt) {
+    e.data().set(DataKey::LockOffset, offset)
+}
+
 pub fn set_last_voyage_id(e: &Env, voyage_id: i32) {
     e.data().set(DataKey::LastVoyage, voyage_id)
 }
@@ -145,3 +540,31 @@ pub fn set_last_raid(e: &Env) {
 pub fn set_target_raid_interval(e: &Env, interval: u32) {
     e.data().set(DataKey::TgtRaidInt, interval)
 }
+
+pub fn set_raid_commit(e: &Env, request_id: u32, commitment: RaidCommit) {
+    e.data().set(DataKey::RaidCmt(request_id), commitment)
+}
+
+pub fn remove_raid_commit(e: &Env, request_id: u32) {
+    e.data().remove(DataKey::RaidCmt(request_id));
+}
+
+pub fn set_oracle(e: &Env, oracle_id: BytesN<32>) {
+    e.data().set(DataKey::Oracle, oracle_id)
+}
+
+pub fn set_wrapped(e: &Env, token_id: BytesN<32>) {
+    e.data().set(DataKey::Wrapped, token_id)
+}
+
+pub fn set_raid_request(e: &Env, request_id: u32, request: RaidRequest) {
+    e.data().set(DataKey::RaidReq(request_id), request)
+}
+
+pub fn remove_raid_request(e: &Env, request_id: u32) {
+    e.data().remove(DataKey::RaidReq(request_id));
+}
+
+pub fn set_last_request_id(e: &Env, request_id: u32) {
+    e.data().set(DataKey::LastReqId, request_id)
+}
@@ -0,0 +1,73 @@
+//! Events published by the sea
+//!
+//! Every state change publishes an event so the sea's state can be rebuilt from events alone. Topics are
+//! the event name followed by the identifier that caused the change, and the voyage id for voyage events,
+//! so indexers can filter by user or voyage. The data is a fixed type per event name, amounts of buried
+//! doubloons carry both their doubloon value and the shares that changed hands.
+//!
+//! User events, topics `(name, user)`:
+//! - `bury` (doubloons, shares), `warmup` (doubloons, ready block), `claim_wrmp` (doubloons, shares)
+//! - `unearth` (shares, doubloons), `cooldown` (shares, doubloons, ready block), `cmp_unrth` doubloons
+//! - `lock` (lock index, LockPosition), `unlock` (lock index, doubloons, shares)
+//! - `approve` (spender, doubloons), `xfer` (to, doubloons, shares)
+//! - `wrap` (doubloons, shares), `unwrap` (shares, doubloons), `bounty` doubloons
+//!
+//! Voyage events, topics `(name, user, voyage_id)`:
+//! - `voyage` (voyages, cost), `bond` (voyages, cost, payout, vested doubloons claimed)
+//! - `claim_vest` doubloons, `end_voyage` (voyages, doubloons)
+//! - `raid` (voyager, voyages, cost, odds), followed by `raid_req` or `raid_cmt` (voyager, request id)
+//! - `raid_draw` (voyager, request id) when the oracle's randomness for a commitment arrives
+//! - `raid_end` (voyager, success, voyages plundered, payout), `raid_exp` voyager
+//! - `raid_cncl` (voyager, request id, doubloons refunded) when an unanswered raid is called off
+//! - `new_voyage` VoyageInfo, `new_bond` (VoyageInfo, vest length), `fix_vyg` (positions, closed)
+//! - `set_ctrl` (bcv, floor, decay length)
+//!
+//! Captain events, topics `(name, captain)`, carry the new value of the parameter they are named after:
+//! `init` (base token, rate, target raid interval), `set_rfv` (asset, value), and `set_epoch`,
+//! `set_bounty`, `set_rwdcap`, `set_warmup`, `set_cooldn`, `set_exit_p`, `set_oracle` and `set_wrap`.
+//! Handovers publish `prop_capn` (nominee, expiry block), `cncl_capn` nominee, and `acpt_capn` or
+//! `rnce_capn` with the new captain, all under the captain being replaced. Roles publish `grant_role`
+//! and `rvke_role` (role, identity), `init` is followed by a `grant_role` for each role. `set_cncl`
+//! (Council, new captain) hands the captaincy to the council, changes made by the council's actions are
+//! published under the sea itself.
+//!
+//! Council events, topics `(name, member)`: `prop_act` (action id, AdminAction, expiry block), `aprv_act`
+//! action id and `exec_act` action id, followed by the events of the executed action.
+//!
+//! Role events, topics `(name, role holder)`, are published by the treasurer (`xfer_held` (token, to,
+//! amount)), the rate keeper (`set_rate` and `set_tgt_ri` with their new value) and the guardian
+//! (`pause` and `unpause`, without data). The voyage master is the user in `new_voyage` and `new_bond`.
+//!
+//! Rebases publish one `rebase` per settled epoch with topics `(name, epoch)` and data (doubloons
+//! distributed in the epoch, index after it).
+
+use crate::captain::read_captain;
+use soroban_auth::Identifier;
+use soroban_sdk::{Env, IntoVal, RawVal, Symbol};
+
+/// Publishes an event about a user's buried doubloons
+pub fn user_event<D: IntoVal<Env, RawVal>>(e: &Env, name: Symbol, user: Identifier, data: D) {
+    e.events().publish((name, user), data);
+}
+
+/// Publishes an event about a user's position on a voyage
+pub fn voyage_event<D: IntoVal<Env, RawVal>>(
+    e: &Env,
+    name: Symbol,
+    user: Identifier,
+    voyage_id: i32,
+    data: D,
+) {
+    e.events().publish((name, user, voyage_id), data);
+}
+
+/// Publishes an event about a change made by the holder of a role, the invoker
+pub fn role_event<D: IntoVal<Env, RawVal>>(e: &Env, name: Symbol, data: D) {
+    e.events()
+        .publish((name, Identifier::from(e.invoker())), data);
+}
+
+/// Publishes an event about a change made by the captain
+pub fn captain_event<D: IntoVal<Env, RawVal>>(e: &Env, name: Symbol, data: D) {
+    e.events().publish((name, read_captain(&e)), data);
+}
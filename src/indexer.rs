@@ -0,0 +1,1119 @@
+//! Off-chain decoder for the sea's events
+//!
+//! Decodes the events described in `events` into `SeaEvent`s and replays them into `SeaModel`, an
+//! in-memory copy of the sea's buried balances, queues, locks, voyages, bonds and raids. The model only
+//! uses what the events carry, so comparing it against the contract's views checks that the events are
+//! enough to rebuild the sea's state. Only built with the `indexer` feature, it isn't part of the contract.
+//! Events are read as the `ContractEvent` XDR found in transaction metadata, so the model can follow a
+//! live network and doesn't need `testutils`.
+
+use crate::data_management::SCALER;
+use crate::seven_seas::{
+    ActionProp, AdminAction, Council, LockPosition, Role, RoleKey, VoyageInfo,
+};
+use soroban_auth::Identifier;
+use soroban_sdk::xdr::{ContractEvent, ContractEventBody, ContractEventType, Hash, ScVal, ScVec};
+use soroban_sdk::{vec, BigInt, BytesN, Env, Map, RawVal, Symbol, TryFromVal, Vec};
+use std::vec::Vec as StdVec;
+
+/// A sea event decoded from its topics and data, see `events` for the schema
+#[derive(Clone)]
+pub enum SeaEvent {
+    Bury {
+        user: Identifier,
+        amount: BigInt,
+        shares: BigInt,
+    },
+    Warmup {
+        user: Identifier,
+        amount: BigInt,
+        ready: u32,
+    },
+    ClaimWarmup {
+        user: Identifier,
+        amount: BigInt,
+        shares: BigInt,
+    },
+    Unearth {
+        user: Identifier,
+        shares: BigInt,
+        amount: BigInt,
+    },
+    Cooldown {
+        user: Identifier,
+        shares: BigInt,
+        amount: BigInt,
+        ready: u32,
+    },
+    CompleteUnearth {
+        user: Identifier,
+        amount: BigInt,
+    },
+    Lock {
+        user: Identifier,
+        lock_idx: u32,
+        position: LockPosition,
+    },
+    Unlock {
+        user: Identifier,
+        lock_idx: u32,
+        amount: BigInt,
+        shares: BigInt,
+    },
+    Approve {
+        from: Identifier,
+        spender: Identifier,
+        amount: BigInt,
+    },
+    Xfer {
+        from: Identifier,
+        to: Identifier,
+        amount: BigInt,
+        shares: BigInt,
+    },
+    Wrap {
+        user: Identifier,
+        amount: BigInt,
+        shares: BigInt,
+    },
+    Unwrap {
+        user: Identifier,
+        shares: BigInt,
+        amount: BigInt,
+    },
+    Bounty {
+        user: Identifier,
+        amount: BigInt,
+    },
+    Voyage {
+        user: Identifier,
+        voyage_id: i32,
+        n_vygs: BigInt,
+        cost: BigInt,
+    },
+    Bond {
+        user: Identifier,
+        voyage_id: i32,
+        n_vygs: BigInt,
+        cost: BigInt,
+        payout: BigInt,
+        claimed: BigInt,
+    },
+    ClaimVest {
+        user: Identifier,
+        voyage_id: i32,
+        amount: BigInt,
+    },
+    EndVoyage {
+        user: Identifier,
+        voyage_id: i32,
+        n_vygs: BigInt,
+        amount: BigInt,
+    },
+    Raid {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+        n_vygs: BigInt,
+        cost: BigInt,
+        odds: u32,
+    },
+    RaidRequest {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+        request_id: u32,
+    },
+    RaidCommit {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+        request_id: u32,
+    },
+    RaidDrawn {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+        request_id: u32,
+    },
+    RaidEnd {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+        success: bool,
+        plundered: BigInt,
+        payout: BigInt,
+    },
+    RaidExpired {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+    },
+    RaidCancelled {
+        raider: Identifier,
+        voyage_id: i32,
+        voyager: Identifier,
+        request_id: u32,
+        refund: BigInt,
+    },
+    NewVoyage {
+        voyage_id: i32,
+        info: VoyageInfo,
+    },
+    NewBond {
+        voyage_id: i32,
+        info: VoyageInfo,
+        vest_len: u32,
+    },
+    FixVoyage {
+        voyage_id: i32,
+        positions: Map<Identifier, BigInt>,
+        closed: BigInt,
+    },
+    PriceControl {
+        voyage_id: i32,
+        bcv: BigInt,
+        floor: BigInt,
+        decay_len: u32,
+    },
+    Rebase {
+        epoch: u32,
+        distributed: BigInt,
+        index: BigInt,
+    },
+    Captain {
+        captain: Identifier,
+        change: CaptainChange,
+    },
+    Pause {
+        guardian: Identifier,
+        paused: bool,
+    },
+    ProposeAction {
+        member: Identifier,
+        action_id: u32,
+        action: AdminAction,
+        expires: u32,
+    },
+    ApproveAction {
+        member: Identifier,
+        action_id: u32,
+    },
+    ExecuteAction {
+        member: Identifier,
+        action_id: u32,
+    },
+}
+
+/// A change made by the captain, or by the role holder for `xfer_held`, `set_rate` and `set_tgt_ri`,
+/// named after the entry point that made it
+#[derive(Clone)]
+pub enum CaptainChange {
+    Init {
+        base_token: BytesN<32>,
+        rate: BigInt,
+        tgt_raid: u32,
+    },
+    XferHeld {
+        token: BytesN<32>,
+        to: Identifier,
+        amount: BigInt,
+    },
+    SetRfv {
+        asset: BytesN<32>,
+        value: BigInt,
+    },
+    ProposeCaptain {
+        nominee: Identifier,
+        expires: u32,
+    },
+    CancelCaptain(Identifier),
+    SetCaptain(Identifier),
+    GrantRole {
+        role: Role,
+        id: Identifier,
+    },
+    RevokeRole {
+        role: Role,
+        id: Identifier,
+    },
+    SetCouncil {
+        council: Council,
+        captain: Identifier,
+    },
+    SetRate(BigInt),
+    SetEpoch(u32),
+    SetBounty(BigInt),
+    SetRewardCap(BigInt),
+    SetWarmup(u32),
+    SetCooldown(u32),
+    SetTargetRaid(u32),
+    SetExitPenalty(BigInt),
+    SetOracle(BytesN<32>),
+    SetWrapped(BytesN<32>),
+}
+
+// converts a raw value to a type, or returns None if it has the wrong type
+fn val<T: TryFromVal<Env, RawVal>>(e: &Env, v: RawVal) -> Option<T> {
+    T::try_from_val(e, v).ok()
+}
+
+// returns a topic, or None if there aren't enough topics
+fn topic<T: TryFromVal<Env, RawVal>>(e: &Env, topics: &Vec<RawVal>, i: u32) -> Option<T> {
+    val(e, topics.get(i)?.ok()?)
+}
+
+// converts an XDR value to a raw value, or returns None if it can't be represented in the env
+fn raw(e: &Env, v: &ScVal) -> Option<RawVal> {
+    RawVal::try_from_val(e, v.clone()).ok()
+}
+
+/// Decodes a sea event from its XDR topics and data, returns None for events the sea doesn't publish
+pub fn decode(e: &Env, topics: &ScVec, data: &ScVal) -> Option<SeaEvent> {
+    let mut raw_topics = Vec::new(e);
+    for t in topics.iter() {
+        raw_topics.push_back(raw(e, t)?);
+    }
+    decode_raw(e, raw_topics, raw(e, data)?)
+}
+
+fn decode_raw(e: &Env, topics: Vec<RawVal>, data: RawVal) -> Option<SeaEvent> {
+    let symbol: Symbol = topic(e, &topics, 0)?;
+    let symbol = symbol.to_str();
+    let name: &str = symbol.as_ref();
+    let event = match name {
+        "rebase" => {
+            let (distributed, index) = val(e, data)?;
+            SeaEvent::Rebase {
+                epoch: topic(e, &topics, 1)?,
+                distributed,
+                index,
+            }
+        }
+        "bury" | "warmup" | "claim_wrmp" | "unearth" | "cooldown" | "cmp_unrth" | "lock"
+        | "unlock" | "approve" | "xfer" | "wrap" | "unwrap" | "bounty" => {
+            decode_user(e, name, topic(e, &topics, 1)?, data)?
+        }
+        "pause" | "unpause" => SeaEvent::Pause {
+            guardian: topic(e, &topics, 1)?,
+            paused: name == "pause",
+        },
+        "prop_act" => {
+            let (action_id, action, expires) = val(e, data)?;
+            SeaEvent::ProposeAction {
+                member: topic(e, &topics, 1)?,
+                action_id,
+                action,
+                expires,
+            }
+        }
+        "aprv_act" => SeaEvent::ApproveAction {
+            member: topic(e, &topics, 1)?,
+            action_id: val(e, data)?,
+        },
+        "exec_act" => SeaEvent::ExecuteAction {
+            member: topic(e, &topics, 1)?,
+            action_id: val(e, data)?,
+        },
+        "init" | "xfer_held" | "set_rfv" | "prop_capn" | "cncl_capn" | "acpt_capn"
+        | "rnce_capn" | "grant_role" | "rvke_role" | "set_cncl" | "set_rate" | "set_epoch"
+        | "set_bounty" | "set_rwdcap" | "set_warmup" | "set_cooldn" | "set_tgt_ri"
+        | "set_exit_p" | "set_oracle" | "set_wrap" => SeaEvent::Captain {
+            captain: topic(e, &topics, 1)?,
+            change: decode_captain(e, name, data)?,
+        },
+        _ => decode_voyage(e, name, topic(e, &topics, 1)?, topic(e, &topics, 2)?, data)?,
+    };
+    Some(event)
+}
+
+fn decode_user(e: &Env, name: &str, user: Identifier, data: RawVal) -> Option<SeaEvent> {
+    let event = match name {
+        "bury" => {
+            let (amount, shares) = val(e, data)?;
+            SeaEvent::Bury {
+                user,
+                amount,
+                shares,
+            }
+        }
+        "warmup" => {
+            let (amount, ready) = val(e, data)?;
+            SeaEvent::Warmup {
+                user,
+                amount,
+                ready,
+            }
+        }
+        "claim_wrmp" => {
+            let (amount, shares) = val(e, data)?;
+            SeaEvent::ClaimWarmup {
+                user,
+                amount,
+                shares,
+            }
+        }
+        "unearth" => {
+            let (shares, amount) = val(e, data)?;
+            SeaEvent::Unearth {
+                user,
+                shares,
+                amount,
+            }
+        }
+        "cooldown" => {
+            let (shares, amount, ready) = val(e, data)?;
+            SeaEvent::Cooldown {
+                user,
+                shares,
+                amount,
+                ready,
+            }
+        }
+        "cmp_unrth" => SeaEvent::CompleteUnearth {
+            user,
+            amount: val(e, data)?,
+        },
+        "lock" => {
+            let (lock_idx, position) = val(e, data)?;
+            SeaEvent::Lock {
+                user,
+                lock_idx,
+                position,
+            }
+        }
+        "unlock" => {
+            let (lock_idx, amount, shares) = val(e, data)?;
+            SeaEvent::Unlock {
+                user,
+                lock_idx,
+                amount,
+                shares,
+            }
+        }
+        "approve" => {
+            let (spender, amount) = val(e, data)?;
+            SeaEvent::Approve {
+                from: user,
+                spender,
+                amount,
+            }
+        }
+        "xfer" => {
+            let (to, amount, shares) = val(e, data)?;
+            SeaEvent::Xfer {
+                from: user,
+                to,
+                amount,
+                shares,
+            }
+        }
+        "wrap" => {
+            let (amount, shares) = val(e, data)?;
+            SeaEvent::Wrap {
+                user,
+                amount,
+                shares,
+            }
+        }
+        "unwrap" => {
+            let (shares, amount) = val(e, data)?;
+            SeaEvent::Unwrap {
+                user,
+                shares,
+                amount,
+            }
+        }
+        "bounty" => SeaEvent::Bounty {
+            user,
+            amount: val(e, data)?,
+        },
+        _ => return None,
+    };
+    Some(event)
+}
+
+fn decode_voyage(
+    e: &Env,
+    name: &str,
+    user: Identifier,
+    voyage_id: i32,
+    data: RawVal,
+) -> Option<SeaEvent> {
+    let event = match name {
+        "voyage" => {
+            let (n_vygs, cost) = val(e, data)?;
+            SeaEvent::Voyage {
+                user,
+                voyage_id,
+                n_vygs,
+                cost,
+            }
+        }
+        "bond" => {
+            let (n_vygs, cost, payout, claimed) = val(e, data)?;
+            SeaEvent::Bond {
+                user,
+                voyage_id,
+                n_vygs,
+                cost,
+                payout,
+                claimed,
+            }
+        }
+        "claim_vest" => SeaEvent::ClaimVest {
+            user,
+            voyage_id,
+            amount: val(e, data)?,
+        },
+        "end_voyage" => {
+            let (n_vygs, amount) = val(e, data)?;
+            SeaEvent::EndVoyage {
+                user,
+                voyage_id,
+                n_vygs,
+                amount,
+            }
+        }
+        "raid" => {
+            let (voyager, n_vygs, cost, odds) = val(e, data)?;
+            SeaEvent::Raid {
+                raider: user,
+                voyage_id,
+                voyager,
+                n_vygs,
+                cost,
+                odds,
+            }
+        }
+        "raid_req" => {
+            let (voyager, request_id) = val(e, data)?;
+            SeaEvent::RaidRequest {
+                raider: user,
+                voyage_id,
+                voyager,
+                request_id,
+            }
+        }
+        "raid_cmt" => {
+            let (voyager, request_id) = val(e, data)?;
+            SeaEvent::RaidCommit {
+                raider: user,
+                voyage_id,
+                voyager,
+                request_id,
+            }
+        }
+        "raid_draw" => {
+            let (voyager, request_id) = val(e, data)?;
+            SeaEvent::RaidDrawn {
+                raider: user,
+                voyage_id,
+                voyager,
+                request_id,
+            }
+        }
+        "raid_end" => {
+            let (voyager, success, plundered, payout) = val(e, data)?;
+            SeaEvent::RaidEnd {
+                raider: user,
+                voyage_id,
+                voyager,
+                success,
+                plundered,
+                payout,
+            }
+        }
+        "raid_exp" => SeaEvent::RaidExpired {
+            raider: user,
+            voyage_id,
+            voyager: val(e, data)?,
+        },
+        "raid_cncl" => {
+            let (voyager, request_id, refund) = val(e, data)?;
+            SeaEvent::RaidCancelled {
+                raider: user,
+                voyage_id,
+                voyager,
+                request_id,
+                refund,
+            }
+        }
+        "new_voyage" => SeaEvent::NewVoyage {
+            voyage_id,
+            info: val(e, data)?,
+        },
+        "new_bond" => {
+            let (info, vest_len) = val(e, data)?;
+            SeaEvent::NewBond {
+                voyage_id,
+                info,
+                vest_len,
+            }
+        }
+        "fix_vyg" => {
+            let (positions, closed) = val(e, data)?;
+            SeaEvent::FixVoyage {
+                voyage_id,
+                positions,
+                closed,
+            }
+        }
+        "set_ctrl" => {
+            let (bcv, floor, decay_len) = val(e, data)?;
+            SeaEvent::PriceControl {
+                voyage_id,
+                bcv,
+                floor,
+                decay_len,
+            }
+        }
+        _ => return None,
+    };
+    Some(event)
+}
+
+fn decode_captain(e: &Env, name: &str, data: RawVal) -> Option<CaptainChange> {
+    let change = match name {
+        "init" => {
+            let (base_token, rate, tgt_raid) = val(e, data)?;
+            CaptainChange::Init {
+                base_token,
+                rate,
+                tgt_raid,
+            }
+        }
+        "xfer_held" => {
+            let (token, to, amount) = val(e, data)?;
+            CaptainChange::XferHeld { token, to, amount }
+        }
+        "set_rfv" => {
+            let (asset, value) = val(e, data)?;
+            CaptainChange::SetRfv { asset, value }
+        }
+        "prop_capn" => {
+            let (nominee, expires) = val(e, data)?;
+            CaptainChange::ProposeCaptain { nominee, expires }
+        }
+        "cncl_capn" => CaptainChange::CancelCaptain(val(e, data)?),
+        "acpt_capn" | "rnce_capn" => CaptainChange::SetCaptain(val(e, data)?),
+        "grant_role" => {
+            let (role, id) = val(e, data)?;
+            CaptainChange::GrantRole { role, id }
+        }
+        "rvke_role" => {
+            let (role, id) = val(e, data)?;
+            CaptainChange::RevokeRole { role, id }
+        }
+        "set_cncl" => {
+            let (council, captain) = val(e, data)?;
+            CaptainChange::SetCouncil { council, captain }
+        }
+        "set_rate" => CaptainChange::SetRate(val(e, data)?),
+        "set_epoch" => CaptainChange::SetEpoch(val(e, data)?),
+        "set_bounty" => CaptainChange::SetBounty(val(e, data)?),
+        "set_rwdcap" => CaptainChange::SetRewardCap(val(e, data)?),
+        "set_warmup" => CaptainChange::SetWarmup(val(e, data)?),
+        "set_cooldn" => CaptainChange::SetCooldown(val(e, data)?),
+        "set_tgt_ri" => CaptainChange::SetTargetRaid(val(e, data)?),
+        "set_exit_p" => CaptainChange::SetExitPenalty(val(e, data)?),
+        "set_oracle" => CaptainChange::SetOracle(val(e, data)?),
+        "set_wrap" => CaptainChange::SetWrapped(val(e, data)?),
+        _ => return None,
+    };
+    Some(change)
+}
+
+/// A raid that has been paid for but not settled or expired
+#[derive(Clone)]
+pub struct OpenRaid {
+    pub raider: Identifier,
+    pub voyage_id: i32,
+    pub voyager: Identifier,
+    pub n_vygs: BigInt,
+    pub odds: u32,
+    pub request_id: Option<u32>, // randomness request, set by the event following the raid
+    pub drawn: bool,             // set once the oracle's randomness for a commitment arrives
+}
+
+/// The sea's state rebuilt from its events
+pub struct SeaModel {
+    env: Env,
+    pub captain: Option<Identifier>,
+    pub nominee: Option<Identifier>, // captain proposed by the current captain, if any
+    pub rate: BigInt,
+    pub index: BigInt,
+    pub epoch: u32,
+    pub warmup: u32,
+    pub cooldown: u32,
+    pub tgt_raid: u32,
+    pub supply: BigInt,     // doubloons minted by the sea less those it burned
+    pub ttl_buried: BigInt, // buried shares, including the boosted shares of locks
+    pub ttl_queued: BigInt, // doubloons waiting in warmup and cooldown queues
+    pub buried: Map<Identifier, BigInt>, // buried shares by user
+    pub queued: Map<Identifier, BigInt>, // queued doubloons by user
+    pub locks: Map<Identifier, Vec<LockPosition>>, // lock positions by user
+    pub voyages: Map<i32, VoyageInfo>, // voyages and bond markets by id
+    pub positions: Map<i32, Map<Identifier, BigInt>>, // voyages embarked on by voyage id and user
+    pub bonds: Map<i32, Map<Identifier, BigInt>>, // unclaimed bond payouts by voyage id and user
+    pub raids: StdVec<OpenRaid>,
+    pub last_vyg: i32,
+    pub roles: Map<RoleKey, bool>, // roles held, by role and identity
+    pub paused: bool,
+    pub council: Option<Council>, // set while the council is captain
+    pub actions: Map<u32, ActionProp>, // admin actions proposed to the council that haven't been executed
+}
+
+impl SeaModel {
+    pub fn new(e: &Env) -> SeaModel {
+        SeaModel {
+            env: e.clone(),
+            captain: None,
+            nominee: None,
+            rate: BigInt::zero(e),
+            index: BigInt::zero(e),
+            epoch: 0,
+            warmup: 0,
+            cooldown: 0,
+            tgt_raid: 0,
+            supply: BigInt::zero(e),
+            ttl_buried: BigInt::zero(e),
+            ttl_queued: BigInt::zero(e),
+            buried: Map::new(e),
+            queued: Map::new(e),
+            locks: Map::new(e),
+            voyages: Map::new(e),
+            positions: Map::new(e),
+            bonds: Map::new(e),
+            raids: StdVec::new(),
+            last_vyg: 0,
+            roles: Map::new(e),
+            paused: false,
+            council: None,
+            actions: Map::new(e),
+        }
+    }
+
+    /// Replays the events published by a sea contract, ignoring events from other contracts
+    pub fn replay(&mut self, sea_id: &Hash, events: &[ContractEvent]) {
+        for event in events {
+            if event.contract_id.as_ref() != Some(sea_id)
+                || event.type_ != ContractEventType::Contract
+            {
+                continue;
+            }
+            let ContractEventBody::V0(body) = &event.body;
+            if let Some(event) = decode(&self.env, &body.topics, &body.data) {
+                self.apply(event);
+            }
+        }
+    }
+
+    /// Returns whether an identity holds a role
+    pub fn has_role(&self, role: Role, id: Identifier) -> bool {
+        self.roles.contains_key(RoleKey { role, id })
+    }
+
+    /// Returns a user's buried shares
+    pub fn buried_of(&self, user: Identifier) -> BigInt {
+        self.buried
+            .get(user)
+            .unwrap_or(Ok(BigInt::zero(&self.env)))
+            .unwrap()
+    }
+
+    /// Returns the doubloons a user has waiting in queues
+    pub fn queued_of(&self, user: Identifier) -> BigInt {
+        self.queued
+            .get(user)
+            .unwrap_or(Ok(BigInt::zero(&self.env)))
+            .unwrap()
+    }
+
+    /// Returns a user's lock positions
+    pub fn locks_of(&self, user: Identifier) -> Vec<LockPosition> {
+        self.locks
+            .get(user)
+            .unwrap_or(Ok(Vec::new(&self.env)))
+            .unwrap()
+    }
+
+    /// Returns the number of voyages a user is on
+    pub fn voyages_of(&self, user: Identifier, voyage_id: i32) -> BigInt {
+        nested(&self.env, &self.positions, voyage_id, user)
+    }
+
+    /// Returns a user's unclaimed bond payout
+    pub fn bond_of(&self, user: Identifier, voyage_id: i32) -> BigInt {
+        nested(&self.env, &self.bonds, voyage_id, user)
+    }
+
+    /// Applies a decoded event to the model
+    pub fn apply(&mut self, event: SeaEvent) {
+        let e = self.env.clone();
+        match event {
+            SeaEvent::Bury {
+                user,
+                amount,
+                shares,
+            } => {
+                self.supply = self.supply.clone() - amount;
+                self.add_buried(user, shares);
+            }
+            SeaEvent::Warmup { user, amount, .. } => {
+                self.supply = self.supply.clone() - amount.clone();
+                self.add_queued(user, amount);
+            }
+            SeaEvent::ClaimWarmup {
+                user,
+                amount,
+                shares,
+            } => {
+                self.add_queued(user.clone(), BigInt::zero(&e) - amount);
+                self.add_buried(user, shares);
+            }
+            SeaEvent::Unearth {
+                user,
+                shares,
+                amount,
+            } => {
+                self.add_buried(user, BigInt::zero(&e) - shares);
+                self.supply = self.supply.clone() + amount;
+            }
+            SeaEvent::Cooldown {
+                user,
+                shares,
+                amount,
+                ..
+            } => {
+                self.add_buried(user.clone(), BigInt::zero(&e) - shares);
+                self.add_queued(user, amount);
+            }
+            SeaEvent::CompleteUnearth { user, amount } => {
+                self.add_queued(user, BigInt::zero(&e) - amount.clone());
+                self.supply = self.supply.clone() + amount;
+            }
+            SeaEvent::Lock { user, position, .. } => {
+                self.supply = self.supply.clone() - position.amount.clone();
+                self.ttl_buried = self.ttl_buried.clone() + position.shares.clone();
+                let mut locks = self.locks_of(user.clone());
+                locks.push_back(position);
+                self.locks.set(user, locks);
+            }
+            SeaEvent::Unlock {
+                user,
+                lock_idx,
+                shares,
+                ..
+            } => {
+                let mut locks = self.locks_of(user.clone());
+                let position = locks.get_unchecked(lock_idx).unwrap();
+                locks.remove(lock_idx);
+                self.locks.set(user.clone(), locks);
+                self.ttl_buried = self.ttl_buried.clone() - position.shares;
+                self.add_buried(user, shares);
+            }
+            SeaEvent::Xfer {
+                from, to, shares, ..
+            } => {
+                self.move_buried(from, BigInt::zero(&e) - shares.clone());
+                self.move_buried(to, shares);
+            }
+            // wrapped shares stay buried, they are just held by the wrapped token instead of the user
+            SeaEvent::Wrap { user, shares, .. } => {
+                self.move_buried(user, BigInt::zero(&e) - shares);
+            }
+            SeaEvent::Unwrap { user, shares, .. } => {
+                self.move_buried(user, shares);
+            }
+            SeaEvent::Bounty { amount, .. } => {
+                self.supply = self.supply.clone() + amount;
+            }
+            SeaEvent::Voyage {
+                user,
+                voyage_id,
+                n_vygs,
+                ..
+            } => {
+                self.embark(voyage_id, n_vygs.clone());
+                add_nested(&e, &mut self.positions, voyage_id, user, n_vygs);
+            }
+            SeaEvent::Bond {
+                user,
+                voyage_id,
+                n_vygs,
+                payout,
+                claimed,
+                ..
+            } => {
+                self.embark(voyage_id, n_vygs);
+                self.supply = self.supply.clone() + claimed.clone();
+                add_nested(&e, &mut self.bonds, voyage_id, user, payout - claimed);
+            }
+            SeaEvent::ClaimVest {
+                user,
+                voyage_id,
+                amount,
+            } => {
+                self.supply = self.supply.clone() + amount.clone();
+                add_nested(
+                    &e,
+                    &mut self.bonds,
+                    voyage_id,
+                    user,
+                    BigInt::zero(&e) - amount,
+                );
+            }
+            SeaEvent::EndVoyage {
+                user,
+                voyage_id,
+                n_vygs,
+                amount,
+            } => {
+                self.supply = self.supply.clone() + amount;
+                add_nested(
+                    &e,
+                    &mut self.positions,
+                    voyage_id,
+                    user,
+                    BigInt::zero(&e) - n_vygs,
+                );
+            }
+            SeaEvent::Raid {
+                raider,
+                voyage_id,
+                voyager,
+                n_vygs,
+                cost,
+                odds,
+            } => {
+                self.supply = self.supply.clone() - cost;
+                self.raids.push(OpenRaid {
+                    raider,
+                    voyage_id,
+                    voyager,
+                    n_vygs,
+                    odds,
+                    request_id: None,
+                    drawn: false,
+                });
+            }
+            SeaEvent::RaidRequest {
+                raider, request_id, ..
+            }
+            | SeaEvent::RaidCommit {
+                raider, request_id, ..
+            } => {
+                // the request is published right after the raid it pays for
+                if let Some(raid) = self
+                    .raids
+                    .iter_mut()
+                    .rev()
+                    .find(|raid| raid.raider == raider && raid.request_id.is_none())
+                {
+                    raid.request_id = Some(request_id);
+                }
+            }
+            SeaEvent::RaidDrawn { request_id, .. } => {
+                if let Some(raid) = self
+                    .raids
+                    .iter_mut()
+                    .find(|raid| raid.request_id == Some(request_id))
+                {
+                    raid.drawn = true;
+                }
+            }
+            SeaEvent::RaidEnd {
+                raider,
+                voyage_id,
+                voyager,
+                plundered,
+                payout,
+                ..
+            } => {
+                self.remove_raid(&raider, voyage_id);
+                self.supply = self.supply.clone() + payout;
+                add_nested(
+                    &e,
+                    &mut self.positions,
+                    voyage_id,
+                    voyager,
+                    BigInt::zero(&e) - plundered,
+                );
+            }
+            SeaEvent::RaidExpired {
+                raider, voyage_id, ..
+            } => {
+                self.remove_raid(&raider, voyage_id);
+            }
+            SeaEvent::RaidCancelled {
+                request_id, refund, ..
+            } => {
+                self.raids
+                    .retain(|raid| raid.request_id != Some(request_id));
+                self.supply = self.supply.clone() + refund;
+            }
+            SeaEvent::NewVoyage { voyage_id, info }
+            | SeaEvent::NewBond {
+                voyage_id, info, ..
+            } => {
+                self.voyages.set(voyage_id, info);
+                self.last_vyg = voyage_id;
+            }
+            SeaEvent::FixVoyage {
+                voyage_id,
+                positions,
+                ..
+            } => {
+                // users left out of the repair keep their position, users named with zero lose it
+                let mut repaired = self
+                    .positions
+                    .get(voyage_id)
+                    .unwrap_or(Ok(Map::new(&e)))
+                    .unwrap();
+                for position in positions.iter() {
+                    let (user, amount) = position.unwrap();
+                    if amount > BigInt::zero(&e) {
+                        repaired.set(user, amount);
+                    } else {
+                        repaired.remove(user);
+                    }
+                }
+                self.positions.set(voyage_id, repaired);
+            }
+            SeaEvent::Rebase { epoch, index, .. } => {
+                self.epoch = epoch;
+                self.index = index;
+            }
+            SeaEvent::Captain { captain, change } => self.apply_captain(captain, change),
+            SeaEvent::Pause { paused, .. } => self.paused = paused,
+            SeaEvent::ProposeAction {
+                member,
+                action_id,
+                action,
+                expires,
+            } => {
+                let approvals = vec![&self.env, member];
+                self.actions.set(
+                    action_id,
+                    ActionProp {
+                        action,
+                        approvals,
+                        expires,
+                    },
+                );
+            }
+            SeaEvent::ApproveAction { member, action_id } => {
+                let mut proposal = self.actions.get(action_id).unwrap().unwrap();
+                proposal.approvals.push_back(member);
+                self.actions.set(action_id, proposal);
+            }
+            SeaEvent::ExecuteAction { action_id, .. } => {
+                self.actions.remove(action_id);
+            }
+            SeaEvent::Approve { .. } | SeaEvent::PriceControl { .. } => {}
+        }
+    }
+
+    fn apply_captain(&mut self, captain: Identifier, change: CaptainChange) {
+        match change {
+            CaptainChange::Init { rate, tgt_raid, .. } => {
+                self.captain = Some(captain);
+                self.rate = rate;
+                self.tgt_raid = tgt_raid;
+                self.index = BigInt::from_i64(&self.env, SCALER * SCALER);
+            }
+            CaptainChange::ProposeCaptain { nominee, .. } => self.nominee = Some(nominee),
+            CaptainChange::CancelCaptain(_) => self.nominee = None,
+            CaptainChange::SetCaptain(captain) => {
+                self.captain = Some(captain);
+                self.nominee = None;
+                self.council = None;
+            }
+            CaptainChange::SetCouncil { council, captain } => {
+                self.captain = Some(captain);
+                self.council = Some(council);
+            }
+            CaptainChange::GrantRole { role, id } => self.roles.set(RoleKey { role, id }, true),
+            CaptainChange::RevokeRole { role, id } => {
+                self.roles.remove(RoleKey { role, id });
+            }
+            CaptainChange::SetRate(rate) => self.rate = rate,
+            CaptainChange::SetWarmup(blocks) => self.warmup = blocks,
+            CaptainChange::SetCooldown(blocks) => self.cooldown = blocks,
+            CaptainChange::SetTargetRaid(interval) => self.tgt_raid = interval,
+            _ => {}
+        }
+    }
+
+    // adds shares to a user's buried shares and the total buried shares
+    fn add_buried(&mut self, user: Identifier, shares: BigInt) {
+        self.ttl_buried = self.ttl_buried.clone() + shares.clone();
+        self.move_buried(user, shares);
+    }
+
+    // adds shares to a user's buried shares without changing the total
+    fn move_buried(&mut self, user: Identifier, shares: BigInt) {
+        let buried = self.buried_of(user.clone()) + shares;
+        self.buried.set(user, buried);
+    }
+
+    fn add_queued(&mut self, user: Identifier, amount: BigInt) {
+        self.ttl_queued = self.ttl_queued.clone() + amount.clone();
+        let queued = self.queued_of(user.clone()) + amount;
+        self.queued.set(user, queued);
+    }
+
+    fn embark(&mut self, voyage_id: i32, n_vygs: BigInt) {
+        let mut info = self.voyages.get(voyage_id).unwrap().unwrap();
+        info.n_embarked = info.n_embarked + n_vygs;
+        self.voyages.set(voyage_id, info);
+    }
+
+    fn remove_raid(&mut self, raider: &Identifier, voyage_id: i32) {
+        if let Some(i) = self
+            .raids
+            .iter()
+            .position(|raid| &raid.raider == raider && raid.voyage_id == voyage_id)
+        {
+            self.raids.remove(i);
+        }
+    }
+}
+
+// returns the amount stored for a user under a voyage id
+fn nested(
+    e: &Env,
+    map: &Map<i32, Map<Identifier, BigInt>>,
+    voyage_id: i32,
+    user: Identifier,
+) -> BigInt {
+    map.get(voyage_id)
+        .unwrap_or(Ok(Map::new(e)))
+        .unwrap()
+        .get(user)
+        .unwrap_or(Ok(BigInt::zero(e)))
+        .unwrap()
+}
+
+// adds to the amount stored for a user under a voyage id, removing it once it reaches zero
+fn add_nested(
+    e: &Env,
+    map: &mut Map<i32, Map<Identifier, BigInt>>,
+    voyage_id: i32,
+    user: Identifier,
+    amount: BigInt,
+) {
+    let mut users = map.get(voyage_id).unwrap_or(Ok(Map::new(e))).unwrap();
+    let total = users
+        .get(user.clone())
+        .unwrap_or(Ok(BigInt::zero(e)))
+        .unwrap()
+        + amount;
+    if total == BigInt::zero(e) {
+        users.remove(user);
+    } else {
+        users.set(user, total);
+    }
+    map.set(voyage_id, users);
+}
@@ -1,11 +1,24 @@
 #![no_std]
 
-#[cfg(any(test, feature = "testutils"))]
+#[cfg(any(test, feature = "testutils", feature = "indexer"))]
 extern crate std;
 
+mod bonding;
+mod buried_token;
 mod captain;
 mod data_management;
+mod events;
+mod locks;
+mod queues;
+mod raid;
+mod reserves;
 
+#[cfg(feature = "indexer")]
+pub mod indexer;
+pub mod oracle;
+pub mod pricing;
+pub mod randomness;
+pub mod rebase;
 pub mod seven_seas;
 pub mod token {
     soroban_sdk::contractimport!(file = "./soroban_token_spec.wasm");
@@ -0,0 +1,108 @@
+//! Lock-up tiers for buried doubloons
+//!
+//! Doubloons can be buried under a lock for one of a few fixed durations, longer locks earn a larger
+//! boost on their rebase rewards. A lock holds `amount * boost` worth of shares, earning rebase rewards
+//! on all of them, less an offset of `amount * (boost - 1)` doubloons so it is worth `amount` when it
+//! is created. The boosted shares count towards the total buried shares like any others, and the sea
+//! tracks the total offset so the doubloons owed to locks can be valued. Locked positions can't be
+//! transferred, wrapped or unearthed, once a lock expires `unlock` turns it back into buried doubloons.
+
+use crate::data_management::{
+    get_lock_offset, get_total_buried, get_user_buried, get_user_locks, set_lock_offset,
+    set_total_buried, set_user_locks, SCALER,
+};
+use crate::events::user_event;
+use crate::seven_seas::{LockPosition, SevenSeasError};
+use soroban_auth::Identifier;
+use soroban_sdk::{symbol, BigInt, Env};
+
+/// Number of blocks in a week at 6 seconds per block
+pub const WEEK: u32 = 100800;
+
+/// Returns the rebase reward boost, scaled by `SCALER`, for a lock duration in weeks
+pub fn lock_boost(weeks: u32) -> Result<i64, SevenSeasError> {
+    match weeks {
+        1 => Ok(SCALER * 105 / 100),
+        4 => Ok(SCALER * 120 / 100),
+        12 => Ok(SCALER * 150 / 100),
+        52 => Ok(SCALER * 2),
+        _ => Err(SevenSeasError::InvalidLock),
+    }
+}
+
+// doubloons subtracted from the value of a lock's boosted shares
+fn lock_offset(e: &Env, position: &LockPosition) -> BigInt {
+    position.amount.clone() * (position.boost.clone() - BigInt::from_i64(&e, SCALER))
+        / BigInt::from_i64(&e, SCALER)
+}
+
+/// Returns the value of a lock position in doubloons at an index
+pub fn lock_value(e: &Env, position: &LockPosition, index: BigInt) -> BigInt {
+    position.shares.clone() * index / BigInt::from_i64(&e, SCALER * SCALER)
+        - lock_offset(&e, position)
+}
+
+// locks buried doubloons for a number of weeks at an index and returns the block the lock expires at
+pub fn add_lock(
+    e: &Env,
+    user: Identifier,
+    amount: BigInt,
+    weeks: u32,
+    index: BigInt,
+) -> Result<u32, SevenSeasError> {
+    let boost = BigInt::from_i64(&e, lock_boost(weeks)?);
+    let position = LockPosition {
+        amount: amount.clone(),
+        shares: amount * BigInt::from_i64(&e, SCALER * SCALER) * boost.clone()
+            / (index * BigInt::from_i64(&e, SCALER)),
+        boost,
+        unlock: e.ledger().sequence() + weeks * WEEK,
+    };
+    set_total_buried(&e, get_total_buried(&e) + position.shares.clone());
+    set_lock_offset(&e, get_lock_offset(&e) + lock_offset(&e, &position));
+    let unlock = position.unlock;
+    let mut locks = get_user_locks(&e, user.clone());
+    let lock_idx = locks.len();
+    locks.push_back(position.clone());
+    set_user_locks(&e, user.clone(), locks);
+    user_event(&e, symbol!("lock"), user, (lock_idx, position));
+    Ok(unlock)
+}
+
+// removes an expired lock position and returns its value in doubloons at an index
+pub fn remove_lock(
+    e: &Env,
+    user: Identifier,
+    lock_idx: u32,
+    index: BigInt,
+) -> Result<BigInt, SevenSeasError> {
+    let mut locks = get_user_locks(&e, user.clone());
+    if lock_idx >= locks.len() {
+        return Err(SevenSeasError::NoLock);
+    }
+    let position = locks.get_unchecked(lock_idx).unwrap();
+    if e.ledger().sequence() < position.unlock {
+        return Err(SevenSeasError::LockNotExpired);
+    }
+    locks.remove(lock_idx);
+    set_user_locks(&e, user, locks);
+    set_total_buried(&e, get_total_buried(&e) - position.shares.clone());
+    set_lock_offset(&e, get_lock_offset(&e) - lock_offset(&e, &position));
+    Ok(lock_value(&e, &position, index))
+}
+
+/// Returns a user's rebase earning shares per unboosted share, scaled by `SCALER`
+pub fn user_boost(e: &Env, user: Identifier) -> BigInt {
+    let buried = get_user_buried(&e, user.clone());
+    let mut boosted = buried.clone();
+    let mut unboosted = buried;
+    for position in get_user_locks(&e, user).iter() {
+        let position = position.unwrap();
+        boosted = boosted + position.shares.clone();
+        unboosted = unboosted + position.shares * BigInt::from_i64(&e, SCALER) / position.boost;
+    }
+    if unboosted == BigInt::zero(&e) {
+        return BigInt::from_i64(&e, SCALER);
+    }
+    boosted * BigInt::from_i64(&e, SCALER) / unboosted
+}
@@ -0,0 +1,12 @@
+//! Interface of randomness oracle contracts
+//!
+//! Oracles implement `Oracle` and later call `fulfill_rd(request_id, randomness)` on the contract that
+//! made the request. `OracleClient` is generated from the trait, like the `token` client `contractimport!`
+//! generates from its spec.
+use soroban_sdk::{contractclient, Env};
+
+#[contractclient(name = "OracleClient")]
+pub trait Oracle {
+    /// Asks the oracle for randomness, it answers by calling `fulfill_rd(request_id, randomness)` on the invoker
+    fn request(e: Env, request_id: u32);
+}
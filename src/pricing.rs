@@ -0,0 +1,85 @@
+//! Offering pricing engine
+//!
+//! Offerings with a price control are priced off their outstanding debt, in the same way as OHM bonds.
+//! Debt is the doubloon payout sold by the offering that hasn't decayed yet, it decays linearly to
+//! zero over the offering's decay length. The price of a voyage is the control variable times the
+//! ratio of outstanding debt to doubloon supply, and never drops below the offering's floor price. The
+//! supply is every doubloon the sea owes, including buried doubloons and doubloons waiting in queues.
+//!
+//! Debt ratios are scaled by the contract decimals, the control variable is the price in the
+//! voyage asset at a debt ratio of 100%.
+//!
+//! Offerings without a price control can be sold by dutch auction, where the price starts at the
+//! offering price and decays towards a reserve price over the embark window.
+
+use crate::data_management::SCALER;
+use soroban_sdk::{BigInt, Env};
+
+/// Returns the debt left after decaying for `elapsed` blocks, debt decays linearly over `decay_len` blocks
+pub fn decay_debt(e: &Env, debt: BigInt, elapsed: u32, decay_len: u32) -> BigInt {
+    if elapsed >= decay_len {
+        return BigInt::zero(&e);
+    }
+    let decay = debt.clone() * BigInt::from_u32(&e, elapsed) / BigInt::from_u32(&e, decay_len);
+    debt - decay
+}
+
+/// Returns the ratio of outstanding debt to doubloon supply, scaled by the contract decimals
+///
+/// Debt with no supply outstanding counts as a ratio of 100%, so the first sales aren't priced at the floor.
+pub fn debt_ratio(e: &Env, debt: BigInt, supply: BigInt) -> BigInt {
+    if debt <= BigInt::zero(&e) {
+        return BigInt::zero(&e);
+    }
+    if supply <= BigInt::zero(&e) {
+        return BigInt::from_i64(&e, SCALER);
+    }
+    debt * BigInt::from_i64(&e, SCALER) / supply
+}
+
+/// Returns the control variable times the debt ratio, or the floor price if that is higher
+pub fn price(e: &Env, bcv: BigInt, ratio: BigInt, floor: BigInt) -> BigInt {
+    let price = bcv * ratio / BigInt::from_i64(&e, SCALER);
+    if price < floor {
+        return floor;
+    }
+    price
+}
+
+/// Returns the price of a linear dutch auction, decaying from `start_price` to `reserve` over `duration` blocks
+pub fn linear_decay(
+    e: &Env,
+    start_price: BigInt,
+    reserve: BigInt,
+    elapsed: u32,
+    duration: u32,
+) -> BigInt {
+    if elapsed >= duration {
+        return reserve;
+    }
+    let excess = start_price.clone() - reserve;
+    start_price - excess * BigInt::from_u32(&e, elapsed) / BigInt::from_u32(&e, duration)
+}
+
+/// Returns the price of an exponential dutch auction, where the premium over `reserve` halves every `half_life` blocks
+///
+/// The premium is halved once per whole half life and interpolated linearly within the current one.
+pub fn exp_decay(
+    e: &Env,
+    start_price: BigInt,
+    reserve: BigInt,
+    elapsed: u32,
+    half_life: u32,
+) -> BigInt {
+    let two = BigInt::from_u32(&e, 2);
+    let mut excess = start_price - reserve.clone();
+    for _ in 0..elapsed / half_life {
+        if excess == BigInt::zero(&e) {
+            break;
+        }
+        excess = excess / two.clone();
+    }
+    let into_half_life = BigInt::from_u32(&e, elapsed % half_life);
+    excess = excess.clone() - excess * into_half_life / (BigInt::from_u32(&e, half_life) * two);
+    reserve + excess
+}
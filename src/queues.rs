@@ -0,0 +1,78 @@
+//! Warmup and cooldown queues for burying and unearthing
+//!
+//! With a warmup set, buried doubloons wait in the user's warmup queue and only become buried shares,
+//! earning rebase rewards, once they are claimed after the warmup. With a cooldown set, unearthing is
+//! requested instead, the shares are converted to doubloons at request time and wait in the user's
+//! cooldown queue until they can be claimed. Doubloons in either queue earn nothing.
+
+use crate::data_management::{
+    get_total_queued, get_user_cooldown, get_user_warmup, set_total_queued, set_user_cooldown,
+    set_user_warmup,
+};
+use crate::seven_seas::{QueueEntry, SevenSeasError};
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env, Vec};
+
+// splits a queue into the doubloons that are ready to be claimed and the entries still waiting
+fn take_ready(e: &Env, queue: Vec<QueueEntry>) -> (BigInt, Vec<QueueEntry>) {
+    let block_now = e.ledger().sequence();
+    let mut ready = BigInt::zero(&e);
+    let mut waiting = Vec::new(&e);
+    for entry in queue.iter() {
+        let entry = entry.unwrap();
+        if entry.ready <= block_now {
+            ready = ready + entry.amount;
+        } else {
+            waiting.push_back(entry);
+        }
+    }
+    (ready, waiting)
+}
+
+// adds doubloons to a user's warmup queue and returns the block they can be claimed at
+pub fn queue_warmup(e: &Env, user: Identifier, amount: BigInt, blocks: u32) -> u32 {
+    let ready = e.ledger().sequence() + blocks;
+    let mut queue = get_user_warmup(&e, user.clone());
+    queue.push_back(QueueEntry {
+        amount: amount.clone(),
+        ready,
+    });
+    set_user_warmup(&e, user, queue);
+    set_total_queued(&e, get_total_queued(&e) + amount);
+    ready
+}
+
+// removes the warmed up doubloons from a user's warmup queue and returns the amount
+pub fn claim_warmup(e: &Env, user: Identifier) -> Result<BigInt, SevenSeasError> {
+    let (ready, waiting) = take_ready(&e, get_user_warmup(&e, user.clone()));
+    if ready == BigInt::zero(&e) {
+        return Err(SevenSeasError::NothingToClaim);
+    }
+    set_user_warmup(&e, user, waiting);
+    set_total_queued(&e, get_total_queued(&e) - ready.clone());
+    Ok(ready)
+}
+
+// adds doubloons to a user's cooldown queue and returns the block they can be claimed at
+pub fn queue_cooldown(e: &Env, user: Identifier, amount: BigInt, blocks: u32) -> u32 {
+    let ready = e.ledger().sequence() + blocks;
+    let mut queue = get_user_cooldown(&e, user.clone());
+    queue.push_back(QueueEntry {
+        amount: amount.clone(),
+        ready,
+    });
+    set_user_cooldown(&e, user, queue);
+    set_total_queued(&e, get_total_queued(&e) + amount);
+    ready
+}
+
+// removes the cooled down doubloons from a user's cooldown queue and returns the amount
+pub fn claim_cooldown(e: &Env, user: Identifier) -> Result<BigInt, SevenSeasError> {
+    let (ready, waiting) = take_ready(&e, get_user_cooldown(&e, user.clone()));
+    if ready == BigInt::zero(&e) {
+        return Err(SevenSeasError::NothingToClaim);
+    }
+    set_user_cooldown(&e, user, waiting);
+    set_total_queued(&e, get_total_queued(&e) - ready.clone());
+    Ok(ready)
+}
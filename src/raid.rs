@@ -0,0 +1,138 @@
+use crate::{
+    data_management::{
+        get_last_raid_block, get_target_raid_interval, get_user_voyage, get_voyage, set_last_raid,
+    },
+    events::voyage_event,
+    randomness::RandomnessSource,
+    seven_seas::{
+        burn_token, check_tracked, close_user_voyage, mint_token, voyage_matured, RaidQuote,
+        SevenSeasError,
+    },
+};
+use soroban_auth::Identifier;
+use soroban_sdk::{symbol, BigInt, Env};
+
+// calculate probability of raid - scaled by how long it has been since the last raid - we target 1 raid per target raid interval - probability cant be greater than 1.25%
+// scale by 10000 to avoid floating point math
+pub fn get_raid_probability(e: &Env) -> u32 {
+    let current_block: u32 = e.ledger().sequence();
+    let raid_probability: u64 = (current_block - get_last_raid_block(&e)) as u64 * 10000
+        / get_target_raid_interval(&e) as u64;
+    if raid_probability > 12500 {
+        return 12500;
+    }
+    raid_probability as u32
+}
+
+// check if a raid was successful by comparing a random u32 against the max random number for a successful raid
+pub fn roll_raid<R: RandomnessSource>(raid_probability: u32, randomness: &mut R) -> bool {
+    let max_ok_prng = u32::MAX / 1000000 * raid_probability;
+    randomness.next_u32() < max_ok_prng
+}
+
+// checks that a voyage can be raided and returns the number of voyages raided, the raid cost and the raid probability
+pub fn quote_raid(
+    e: &Env,
+    voyage_id: i32,
+    voyager: Identifier,
+) -> Result<RaidQuote, SevenSeasError> {
+    // voyages can only be raided while they are still at sea
+    let voyage_info = get_voyage(&e, voyage_id)?;
+    check_tracked(&e, voyage_id)?;
+    if voyage_matured(&e, &voyage_info) {
+        return Err(SevenSeasError::VoyageCompleted);
+    }
+    let user_voyage_amt = get_user_voyage(&e, voyager, voyage_id);
+    if user_voyage_amt == BigInt::zero(&e) {
+        return Err(SevenSeasError::NoVoyages);
+    }
+    // calculate the amount of shells required to perform the raid - 1/100th of the voyages' payout
+    let raid_cost = user_voyage_amt.clone() * voyage_info.reward / BigInt::from_i64(&e, 100);
+    Ok(RaidQuote {
+        n_vygs: user_voyage_amt,
+        cost: raid_cost,
+        odds: get_raid_probability(e),
+    })
+}
+
+// burns the raid cost from the raider and returns the quote it was paid at
+pub fn pay_raid(
+    e: &Env,
+    raider: Identifier,
+    voyage_id: i32,
+    voyager: Identifier,
+) -> Result<RaidQuote, SevenSeasError> {
+    let quote = quote_raid(&e, voyage_id, voyager.clone())?;
+    // burn the shells
+    burn_token(&e, raider.clone(), quote.cost.clone());
+    // update last raid block
+    set_last_raid(&e);
+    voyage_event(
+        &e,
+        symbol!("raid"),
+        raider,
+        voyage_id,
+        (
+            voyager,
+            quote.n_vygs.clone(),
+            quote.cost.clone(),
+            quote.odds,
+        ),
+    );
+    Ok(quote)
+}
+
+// refunds a raid the oracle never answered and publishes the refund
+pub fn refund_raid(
+    e: &Env,
+    raider: Identifier,
+    voyage_id: i32,
+    voyager: Identifier,
+    request_id: u32,
+    cost: BigInt,
+) {
+    mint_token(&e, raider.clone(), cost.clone());
+    voyage_event(
+        &e,
+        symbol!("raid_cncl"),
+        raider,
+        voyage_id,
+        (voyager, request_id, cost),
+    );
+}
+
+// settles a paid for raid with the input randomness
+pub fn settle_raid<R: RandomnessSource>(
+    e: &Env,
+    raider: Identifier,
+    voyage_id: i32,
+    voyager: Identifier,
+    n_vygs: BigInt,
+    odds: u32,
+    randomness: &mut R,
+) -> Result<(), SevenSeasError> {
+    // the voyager may have ended their voyage since the raid was paid for, the raider can only plunder what is left
+    let user_voyage_amt = get_user_voyage(&e, voyager.clone(), voyage_id);
+    let mut plunder = n_vygs;
+    if user_voyage_amt < plunder {
+        plunder = user_voyage_amt.clone();
+    }
+    let success = roll_raid(odds, randomness) && plunder > BigInt::zero(&e);
+    let mut payout = BigInt::zero(&e);
+    if success {
+        // raid was successful, user loses the raided voyages, raider gets their payout
+        close_user_voyage(&e, voyager.clone(), voyage_id, plunder.clone());
+        payout = plunder.clone() * get_voyage(&e, voyage_id)?.reward;
+        mint_token(&e, raider.clone(), payout.clone());
+    } else {
+        plunder = BigInt::zero(&e);
+    }
+    voyage_event(
+        &e,
+        symbol!("raid_end"),
+        raider,
+        voyage_id,
+        (voyager, success, plunder, payout),
+    );
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use soroban_sdk::{Bytes, BytesN, Env};
+
+/// Source of the random numbers used to settle raids
+///
+/// The host exposes no randomness a raider can't predict. The contract id and ledger sequence are known
+/// and the close time of the next ledger is easy to guess, so a raider mixing a secret with host data
+/// could grind secrets offline until one wins for every likely close time. Raids only roll randomness
+/// that came from the oracle.
+pub trait RandomnessSource {
+    fn next_u32(&mut self) -> u32;
+}
+
+/// Randomness expanded from a value provided by a randomness oracle
+pub struct OraclePrng {
+    env: Env,
+    seed: Bytes,
+    counter: u32,
+}
+
+impl OraclePrng {
+    pub fn new(e: &Env, randomness: Bytes) -> OraclePrng {
+        OraclePrng {
+            env: e.clone(),
+            seed: randomness,
+            counter: 0,
+        }
+    }
+}
+
+impl RandomnessSource for OraclePrng {
+    fn next_u32(&mut self) -> u32 {
+        let value = hash_u32(&self.env, &self.seed, self.counter);
+        self.counter += 1;
+        value
+    }
+}
+
+// returns the first 4 bytes of sha256(seed || counter) as a u32
+fn hash_u32(e: &Env, seed: &Bytes, counter: u32) -> u32 {
+    let mut msg = seed.clone();
+    msg.extend_from_array(&counter.to_be_bytes());
+    let hash: BytesN<32> = e.compute_hash_sha256(&msg);
+    let mut value: u32 = 0;
+    for i in 0..4 {
+        value = (value << 8) | hash.get_unchecked(i) as u32;
+    }
+    value
+}
@@ -0,0 +1,171 @@
+//! Compounding rebase math
+//!
+//! The index compounds once per epoch by `1 + rate / SCALER`, however long it has been since it was
+//! last updated. The stored index is an anchor at the start of the current epoch, the index for any
+//! later epoch is computed from it directly. Growth over many epochs is computed with exponentiation by
+//! squaring in fixed point, scaled by `SCALER * SCALER` like the index itself.
+//!
+//! Rounding: every fixed point multiplication truncates, losing less than one unit at
+//! `SCALER * SCALER` scale. With a rate of zero or more every intermediate value is at least one, so
+//! each truncation is a relative error below `1 / (SCALER * SCALER)`, and squaring doubles the error
+//! already in the base. Summed over the bits of the exponent the result of `pow_fixed` is never above
+//! the exact power and is below it by a relative error of at most `(epochs + 1) / (SCALER * SCALER)`,
+//! so a million epochs are still accurate to about one part in 10^8. `compound` truncates once more
+//! when applying the growth to the index. Rounding always favours the protocol.
+
+use crate::{
+    data_management::{
+        get_epoch_len, get_epoch_num, get_index, get_last_block, get_rate, get_reward_cap,
+        get_total_buried, set_epoch_num, set_index, set_last_block, SCALER,
+    },
+    reserves::excess_reserves,
+    seven_seas::SevenSeasError,
+};
+use soroban_sdk::{symbol, BigInt, Env};
+
+/// Default number of blocks in a rebase epoch, the rate is the growth per epoch
+pub const EPOCH_LEN: u32 = 100;
+
+/// Most epochs one update settles, each settled epoch publishes its own event
+pub const MAX_EPOCHS: u32 = 100;
+
+/// Raises `base`, scaled by `SCALER * SCALER`, to the power of `exp`, returning a value at the same scale
+pub fn pow_fixed(e: &Env, base: BigInt, exp: u32) -> BigInt {
+    let one = BigInt::from_i64(&e, SCALER * SCALER);
+    let mut result = one.clone();
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base.clone() / one.clone();
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.clone() * base / one.clone();
+        }
+    }
+    result
+}
+
+/// Returns the index after compounding it by `rate`, scaled by the contract decimals, for `epochs` epochs
+pub fn compound(e: &Env, index: BigInt, rate: BigInt, epochs: u32) -> BigInt {
+    if epochs == 0 {
+        return index;
+    }
+    let growth = BigInt::from_i64(&e, SCALER * SCALER) + rate * BigInt::from_i64(&e, SCALER);
+    index * pow_fixed(&e, growth, epochs) / BigInt::from_i64(&e, SCALER * SCALER)
+}
+
+/// Returns the number of whole epochs that have passed since the index was last updated
+pub fn pending_epochs(e: &Env) -> u32 {
+    (e.ledger().sequence() - get_last_block(&e)) / get_epoch_len(&e)
+}
+
+// returns the doubloons distributed to buried doubloons and the index after `epochs` pending epochs
+//
+// the index compounds from the stored anchor until an epoch's rewards are capped by the treasury's excess
+// reserves, by default an epoch can pay out all of the excess. Uncapped rewards only grow from epoch to epoch while the cap only shrinks as the excess is paid
+// out, so once the cap binds it binds for every later epoch, each paying the cap fraction of the excess that
+// is left. The first capped epoch is found by binary search, and the capped epochs after it are paid as a
+// geometric series, so the cost is logarithmic in the number of pending epochs. The series keeps
+// `(1 - cap)^n` of the excess, truncated like `pow_fixed`, and the payout is truncated once more.
+fn advance(e: &Env, epochs: u32) -> (BigInt, BigInt) {
+    let one = BigInt::from_i64(&e, SCALER * SCALER);
+    let zero = BigInt::zero(&e);
+    let rate = get_rate(&e);
+    let total_shares = get_total_buried(&e);
+    let anchor = get_index(&e);
+    let uncapped = |n: u32| compound(&e, anchor.clone(), rate.clone(), n);
+    let paid = |index: BigInt| total_shares.clone() * (index - anchor.clone()) / one.clone();
+    // with nothing buried no rewards are paid, so there is nothing to cap
+    if total_shares <= zero {
+        let index = uncapped(epochs);
+        return (paid(index.clone()), index);
+    }
+    let scaler = BigInt::from_i64(&e, SCALER);
+    let reward_cap = get_reward_cap(&e);
+    let excess = excess_reserves(&e, anchor.clone());
+    // the excess left after `n` uncapped epochs, never below zero
+    let excess_after = |n: u32| {
+        let left = excess.clone() - paid(uncapped(n));
+        if left < zero {
+            zero.clone()
+        } else {
+            left
+        }
+    };
+    let cap_binds = |n: u32| {
+        let distributed = paid(uncapped(n)) - paid(uncapped(n - 1));
+        distributed > excess_after(n - 1) * reward_cap.clone() / scaler.clone()
+    };
+
+    // the first capped epoch, or one past the last pending epoch if the cap never binds
+    let mut low = 1;
+    let mut high = epochs + 1;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if cap_binds(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    let index = uncapped(low - 1);
+    if low > epochs {
+        return (paid(index.clone()), index);
+    }
+    let left = excess_after(low - 1);
+    let kept = pow_fixed(&e, (scaler.clone() - reward_cap) * scaler, epochs - low + 1);
+    let capped = left * (one.clone() - kept) / one.clone();
+    (
+        paid(index.clone()) + capped.clone(),
+        index + capped * one / total_shares,
+    )
+}
+
+/// Returns the index as of the current block, without settling any epochs
+pub fn project_index(e: &Env) -> BigInt {
+    advance(&e, pending_epochs(&e)).1
+}
+
+/// Settles up to `MAX_EPOCHS` of the epochs that have passed since the index was last updated, returning
+/// how many were settled
+///
+/// Publishes a `rebase` event per settled epoch with the epoch number as a topic, and the doubloons
+/// distributed to buried doubloons in that epoch and the index after it as data. Every epoch is measured
+/// from the stored anchor, so the settled index is the one `project_index` returned. Partial epochs carry
+/// over to the next update.
+pub fn settle_epochs(e: &Env) -> u32 {
+    let epochs = pending_epochs(&e).min(MAX_EPOCHS);
+    if epochs == 0 {
+        return 0;
+    }
+    let epoch_num = get_epoch_num(&e);
+    let mut paid = BigInt::zero(&e);
+    let mut index = get_index(&e);
+    for n in 1..=epochs {
+        let (distributed, next) = advance(&e, n);
+        e.events().publish(
+            (symbol!("rebase"), epoch_num + n),
+            (distributed.clone() - paid, next.clone()),
+        );
+        paid = distributed;
+        index = next;
+    }
+    set_index(&e, index);
+    set_last_block(&e, get_last_block(&e) + epochs * get_epoch_len(&e));
+    set_epoch_num(&e, epoch_num + epochs);
+    epochs
+}
+
+/// Settles every pending epoch, failing if more than `MAX_EPOCHS` are pending
+///
+/// Anything that changes what an epoch pays, like buried shares or the rate, settles first so the change
+/// doesn't reach back into epochs that already passed. A longer backlog has to be cleared with `rebase`.
+pub fn settle_pending(e: &Env) -> Result<(), SevenSeasError> {
+    settle_epochs(&e);
+    if pending_epochs(&e) > 0 {
+        return Err(SevenSeasError::EpochsPending);
+    }
+    Ok(())
+}
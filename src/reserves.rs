@@ -0,0 +1,105 @@
+//! Treasury reserve accounting
+//!
+//! Tracks how much of each voyage asset the treasury received from voyages and bonds, valued in
+//! doubloons at a rate the captain sets per asset. Reserves in excess of the doubloons the sea owes
+//! (the doubloons it minted, the value of buried doubloons and the doubloons waiting in warmup and
+//! cooldown queues) back the rebase: each epoch's rebase rewards are capped at the reward cap fraction
+//! of the excess, all of it unless the captain sets a lower cap.
+//!
+//! The supply only counts doubloons minted and burned by the sea, so burying doubloons minted elsewhere
+//! can push it below zero, offsetting the value of those buried doubloons. Only the outstanding total,
+//! which also counts buried and queued doubloons, is meaningful, and it is what offerings are priced off.
+
+use crate::{
+    data_management::{
+        get_lock_offset, get_rate, get_reserve, get_reserve_assets, get_rfv, get_supply,
+        get_total_buried, get_total_queued, has_reserve, set_reserve, set_reserve_assets, SCALER,
+    },
+    rebase::pow_fixed,
+};
+use soroban_sdk::{BigInt, BytesN, Env};
+
+pub fn add_reserve(e: &Env, asset: BytesN<32>, amount: BigInt) {
+    if !has_reserve(&e, asset.clone()) {
+        let mut assets = get_reserve_assets(&e);
+        assets.push_back(asset.clone());
+        set_reserve_assets(&e, assets);
+    }
+    let reserve = get_reserve(&e, asset.clone());
+    set_reserve(&e, asset, reserve + amount);
+}
+
+// reduces the tracked reserve of an asset, amounts beyond the tracked reserve weren't reserves to begin with
+pub fn remove_reserve(e: &Env, asset: BytesN<32>, amount: BigInt) {
+    let reserve = get_reserve(&e, asset.clone());
+    if reserve <= amount {
+        set_reserve(&e, asset, BigInt::zero(&e));
+    } else {
+        set_reserve(&e, asset, reserve - amount);
+    }
+}
+
+/// Returns the value of all tracked reserves in doubloons
+pub fn reserve_value(e: &Env) -> BigInt {
+    let mut value = BigInt::zero(&e);
+    for asset in get_reserve_assets(&e).iter() {
+        let asset = asset.unwrap();
+        value = value
+            + get_reserve(&e, asset.clone()) * get_rfv(&e, asset) / BigInt::from_i64(&e, SCALER);
+    }
+    value
+}
+
+/// Returns the value of buried doubloons at an index, less the offset of locked positions
+pub fn buried_value(e: &Env, index: BigInt) -> BigInt {
+    get_total_buried(&e) * index / BigInt::from_i64(&e, SCALER * SCALER) - get_lock_offset(&e)
+}
+
+/// Returns the doubloons the sea owes at an index, the doubloons it minted, the value of buried doubloons and queued doubloons
+pub fn outstanding(e: &Env, index: BigInt) -> BigInt {
+    get_supply(&e) + buried_value(&e, index) + get_total_queued(&e)
+}
+
+/// Returns the reserves in excess of the doubloons owed at an index, this can be negative
+pub fn excess_reserves(e: &Env, index: BigInt) -> BigInt {
+    reserve_value(&e) - outstanding(&e, index)
+}
+
+/// Returns the number of epochs the current rate can be paid out of excess reserves, or u32::MAX if it pays nothing
+pub fn runway(e: &Env, index: BigInt) -> u32 {
+    let excess = excess_reserves(&e, index.clone());
+    // rewards are paid on every buried share, including the boosted shares of locks
+    let buried = get_total_buried(&e) * index / BigInt::from_i64(&e, SCALER * SCALER);
+    let rate = get_rate(&e);
+    if buried == BigInt::zero(&e) || rate <= BigInt::zero(&e) {
+        return u32::MAX;
+    }
+    if excess <= BigInt::zero(&e) {
+        return 0;
+    }
+    let one = BigInt::from_i64(&e, SCALER * SCALER);
+    let growth = one.clone() + rate * BigInt::from_i64(&e, SCALER);
+    // rewards paid over n epochs are buried * (growth^n - 1)
+    let affordable = |epochs: u32| {
+        buried.clone() * (pow_fixed(&e, growth.clone(), epochs) - one.clone()) / one.clone()
+            <= excess
+    };
+    // find an unaffordable number of epochs by doubling, then binary search below it
+    let mut high: u32 = 1;
+    while affordable(high) {
+        if high > u32::MAX / 2 {
+            return u32::MAX;
+        }
+        high *= 2;
+    }
+    let mut low = high / 2;
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if affordable(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
@@ -1,46 +1,150 @@
-use core::u32::MAX;
-
 use crate::{
-    captain::{check_captain, write_captain},
+    bonding::{add_bond, claim_bond, is_bond_market, vested_amount},
+    buried_token::{
+        doubloons_at, shares_at, spend_allowance, to_doubloons, to_shares, unwrap_buried,
+        verify_and_consume_nonce, wrap_buried, xfer_buried,
+    },
+    captain::{
+        check_captain, check_member, check_role, count_approvals, has_role, read_action,
+        read_captain, read_council, read_proposal, read_roles, remove_action, remove_council,
+        remove_proposal, remove_role, set_action, write_action, write_captain, write_council,
+        write_proposal, write_role,
+    },
     data_management::{
-        get_base_token_client, get_decimals, get_last_raid_block, get_last_voyage_id,
-        get_new_index, get_target_raid_interval, get_total_buried, get_user_buried,
-        get_user_voyage, get_voyage, remove_user_voyage, set_base_token, set_decimals, set_index,
-        set_last_block, set_last_raid, set_last_voyage_id, set_rate, set_target_raid_interval,
-        set_total_buried, set_user_buried, set_user_voyage, set_voyage, SCALER,
+        get_allowance, get_base_token, get_base_token_client, get_bounty, get_cooldown,
+        get_decimals, get_epoch_len, get_epoch_num, get_exit_penalty, get_index, get_last_block,
+        get_last_raid_block, get_last_request_id, get_last_voyage_id, get_new_index, get_nonce,
+        get_oracle, get_paused, get_price_ctrl, get_raid_commit, get_raid_request, get_rate,
+        get_supply, get_target_raid_interval, get_total_buried, get_total_queued, get_user_bond,
+        get_user_buried, get_user_cooldown, get_user_locks, get_user_voyage, get_user_voyage_ids,
+        get_user_warmup, get_voyage, get_voyage_closed, get_voyage_users, get_warmup, has_oracle,
+        has_price_ctrl, has_raid_commit, has_raid_request, has_user_bond, is_voyage_tracked,
+        remove_raid_commit, remove_raid_request, remove_user_voyage, set_allowance, set_base_token,
+        set_bond_vesting, set_bounty, set_cooldown, set_decimals, set_epoch_len, set_exit_penalty,
+        set_index, set_last_block, set_last_request_id, set_last_voyage_id, set_oracle, set_paused,
+        set_price_ctrl, set_raid_commit, set_raid_request, set_rate, set_reward_cap, set_rfv,
+        set_supply, set_target_raid_interval, set_total_buried, set_user_buried, set_user_voyage,
+        set_voyage, set_voyage_closed, set_voyage_tracked, set_warmup, set_wrapped, SCALER,
     },
+    events::{captain_event, role_event, user_event, voyage_event},
+    locks::{add_lock, lock_value, remove_lock, user_boost, WEEK},
+    oracle::OracleClient,
+    pricing::{debt_ratio, decay_debt, exp_decay, linear_decay, price},
+    queues::{claim_cooldown, claim_warmup, queue_cooldown, queue_warmup},
+    raid::{pay_raid, quote_raid, refund_raid, settle_raid},
+    randomness::OraclePrng,
+    rebase::{settle_epochs, settle_pending},
+    reserves::{add_reserve, excess_reserves, outstanding, remove_reserve, runway},
+};
+use soroban_auth::{verify, Identifier, Signature};
+use soroban_sdk::{
+    contracterror, contractimpl, contracttype, symbol, BigInt, Bytes, BytesN, Env, Map, Vec,
 };
-use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{contractimpl, contracttype, symbol, BigInt, BytesN, Env, RawVal};
 
 // ****** Contract Storage *****
 
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
-    BaseToken,              // address of the doubloon token
-    UserBuried(Identifier), // bigint storing the amount of doubloons buried by a user
-    TtlBuried,              // total doubloons buried
-    Rate,                   // per-100-block rebase rate for buried_doubloon tokens
-    Index,                  // rebase index for doubloon tokens
-    LastBlock,              // last block the index was updated at
-    Admin,                  // admin address
-    Decimals,               // decimals for both the index, rate, and buried_doubloon tokens
-    Voyages(i32),           // struct of voyage information
-    LastVoyage,             // stores the id of the last voyage
-    UserVoyage(VoyageKey),  // struct of user voyages
-    LastRaid,               // stores the block the last raid was performed on
-    TgtRaidInt,             // stores the target raid interval (num blocks between raids)
-}
-
-fn subtract_buried(e: &Env, from: Identifier, amount: BigInt) {
+    BaseToken,               // address of the doubloon token
+    UserBuried(Identifier),  // bigint storing the amount of doubloons buried by a user
+    TtlBuried,               // total doubloons buried
+    Rate,                    // per-100-block rebase rate for buried_doubloon tokens
+    Index,                   // rebase index for doubloon tokens
+    LastBlock,               // last block the index was updated at
+    Admin,                   // admin address
+    Decimals,                // decimals for both the index, rate, and buried_doubloon tokens
+    Voyages(i32),            // struct of voyage information
+    LastVoyage,              // stores the id of the last voyage
+    UserVoyage(VoyageKey),   // struct of user voyages
+    UserVygs(Identifier),    // vec of the ids of the voyages a user has an open position on
+    LastRaid,                // stores the block the last raid was performed on
+    TgtRaidInt,              // stores the target raid interval (num blocks between raids)
+    RaidCmt(u32),            // struct of a raid commitment waiting on its randomness and reveal
+    Oracle,                  // address of the randomness oracle contract
+    RaidReq(u32),            // struct of a raid waiting on randomness from the oracle
+    LastReqId,               // stores the id of the last randomness request
+    VygUsers(i32),           // vec of the users with an open position on a voyage
+    VygTrack(i32),           // set once a voyage tracks its positions, legacy ones need fix_vyg
+    VygClosed(i32), // bigint storing the number of voyages redeemed or plundered from a voyage
+    ExitPen,        // bigint storing the penalty taken from voyages ended before they complete
+    BondVest(i32),  // number of blocks payouts vest over for a bond market
+    UserBond(VoyageKey), // struct of a user's vesting position on a bond market
+    PriceCtrl(i32), // struct of the price control for a dynamically priced offering
+    Supply,         // bigint storing the doubloons minted by the sea less those it burned
+    Allowance(AllowanceKey), // bigint storing the buried doubloons a spender can transfer for a user
+    Nonce(Identifier),       // bigint storing the next signature nonce for a user
+    Wrapped,                 // address of the wsDOUBLOON token
+    EpochLen,                // number of blocks in a rebase epoch
+    EpochNum,                // number of rebase epochs settled
+    Bounty,                  // bigint storing the doubloons paid to the caller of `rebase`
+    Reserve(BytesN<32>),     // bigint storing the treasury reserves of a voyage asset
+    Rfv(BytesN<32>),         // bigint storing the value of a voyage asset in doubloons
+    RsvAssets,               // vec of the voyage assets held as reserves
+    RwdCap,   // fraction of excess reserves that can be paid as rebase rewards per epoch
+    Warmup,   // number of blocks newly buried doubloons wait before they can be claimed
+    Cooldown, // number of blocks unearthed doubloons wait before they can be claimed
+    UserWarm(Identifier), // vec of a user's buried doubloons waiting out the warmup
+    UserCool(Identifier), // vec of a user's unearthed doubloons waiting out the cooldown
+    TtlQueued, // bigint storing the doubloons waiting in warmup and cooldown queues
+    UserLocks(Identifier), // vec of a user's locked buried doubloon positions
+    LockOffset, // bigint storing the doubloons subtracted from the value of all locks
+    CapnProp, // struct of the captain's pending handover proposal
+    Role(RoleKey), // bool set while an identity holds a role
+    Roles,    // vec of every role held, by role and identity
+    Paused,   // bool storing whether the guardian has paused the sea
+    Council, // struct of the council members and approval threshold, set while the council is captain
+    Action(u32), // struct of an admin action proposed to the council
+    LastAction, // stores the id of the last admin action proposed to the council
+}
+
+fn grant(e: &Env, role: Role, id: Identifier) {
+    write_role(&e, role.clone(), id.clone());
+    captain_event(&e, symbol!("grant_role"), (role, id));
+}
+
+fn revoke(e: &Env, role: Role, id: Identifier) {
+    remove_role(&e, role.clone(), id.clone());
+    captain_event(&e, symbol!("rvke_role"), (role, id));
+}
+
+// every role, the first captain is granted all of them
+const ROLES: [Role; 4] = [
+    Role::VygMaster,
+    Role::Treasurer,
+    Role::RateKeeper,
+    Role::Guardian,
+];
+
+// revokes every role an identity holds
+fn revoke_all(e: &Env, id: Identifier) {
+    for role in ROLES {
+        if has_role(&e, role.clone(), id.clone()) {
+            revoke(&e, role, id.clone());
+        }
+    }
+}
+
+// checks that the guardian hasn't paused the sea
+fn check_unpaused(e: &Env) -> Result<(), SevenSeasError> {
+    if get_paused(&e) {
+        return Err(SevenSeasError::Paused);
+    }
+    Ok(())
+}
+
+fn subtract_buried(e: &Env, from: Identifier, amount: BigInt) -> Result<(), SevenSeasError> {
     let current = get_user_buried(&e, from.clone());
     if current < amount {
-        panic!("not enough buried doubloons to unearth");
+        if !get_user_locks(&e, from).is_empty() {
+            return Err(SevenSeasError::DoubloonsLocked);
+        }
+        return Err(SevenSeasError::NotEnoughBuried);
     }
     set_user_buried(&e, from, current - amount.clone());
     let total = get_total_buried(e);
     set_total_buried(e, total - amount);
+    Ok(())
 }
 
 fn add_buried(e: &Env, to: Identifier, amount: BigInt) {
@@ -50,12 +154,14 @@ fn add_buried(e: &Env, to: Identifier, amount: BigInt) {
     set_total_buried(e, total + amount);
 }
 
-fn burn_token(e: &Env, from: Identifier, amount: BigInt) {
+pub(crate) fn burn_token(e: &Env, from: Identifier, amount: BigInt) {
+    set_supply(&e, get_supply(&e) - amount.clone());
     let base_token_client = get_base_token_client(&e);
     base_token_client.burn(&Signature::Invoker, &BigInt::zero(&e), &from, &amount)
 }
 
-fn mint_token(e: &Env, to: Identifier, amount: BigInt) {
+pub(crate) fn mint_token(e: &Env, to: Identifier, amount: BigInt) {
+    set_supply(&e, get_supply(&e) + amount.clone());
     let base_token_client = get_base_token_client(&e);
     base_token_client.mint(&Signature::Invoker, &BigInt::zero(&e), &to, &amount)
 }
@@ -69,11 +175,267 @@ fn transfer(e: &Env, contract_id: BytesN<32>, to: Identifier, amount: BigInt) {
     );
 }
 
+// closes some of a user's voyages, either because they were redeemed or plundered
+pub(crate) fn close_user_voyage(e: &Env, user_id: Identifier, voyage_id: i32, amount: BigInt) {
+    let remaining = get_user_voyage(&e, user_id.clone(), voyage_id) - amount.clone();
+    if remaining == BigInt::zero(&e) {
+        remove_user_voyage(&e, user_id.clone(), voyage_id);
+    } else {
+        set_user_voyage(&e, user_id, voyage_id, remaining);
+    }
+    let closed = get_voyage_closed(&e, voyage_id);
+    set_voyage_closed(&e, voyage_id, closed + amount);
+}
+
+// checks that the users' positions and the closed voyages on a voyage add up to the number of voyages embarked on
+fn voyage_reconciles(e: &Env, voyage_id: i32) -> Result<bool, SevenSeasError> {
+    let voyage_info = get_voyage(&e, voyage_id)?;
+    let mut total = get_voyage_closed(&e, voyage_id);
+    for user in get_voyage_users(&e, voyage_id).iter() {
+        total = total + get_user_voyage(&e, user.unwrap(), voyage_id);
+    }
+    Ok(total == voyage_info.n_embarked)
+}
+
+// legacy offerings have to be repaired with fix_vyg before their positions can change
+pub(crate) fn check_tracked(e: &Env, voyage_id: i32) -> Result<(), SevenSeasError> {
+    if !is_voyage_tracked(&e, voyage_id) {
+        return Err(SevenSeasError::Untracked);
+    }
+    Ok(())
+}
+
+// voyages sail until the expiration block (the embark deadline plus the sail duration) and complete after it
+pub(crate) fn voyage_matured(e: &Env, voyage_info: &VoyageInfo) -> bool {
+    e.ledger().sequence() > voyage_info.expiration
+}
+
+// returns the offering's price control with its debt decayed up to the current block
+fn get_decayed_ctrl(e: &Env, voyage_id: i32) -> PriceCtrl {
+    let mut ctrl = get_price_ctrl(&e, voyage_id);
+    let elapsed = e.ledger().sequence() - ctrl.last_decay;
+    ctrl.debt = decay_debt(&e, ctrl.debt, elapsed, ctrl.decay_len);
+    ctrl.last_decay = e.ledger().sequence();
+    ctrl
+}
+
+// returns the current price of a voyage, offerings without a price control are priced by their price mode
+fn get_voyage_price(e: &Env, voyage_id: i32, voyage_info: &VoyageInfo) -> BigInt {
+    if !has_price_ctrl(&e, voyage_id) {
+        let elapsed = e.ledger().sequence().saturating_sub(voyage_info.start_ldgr);
+        return match voyage_info.price_mode.clone() {
+            PriceMode::Fixed => voyage_info.price.clone(),
+            PriceMode::Linear(reserve) => linear_decay(
+                &e,
+                voyage_info.price.clone(),
+                reserve,
+                elapsed,
+                voyage_info.embark_dl - voyage_info.start_ldgr,
+            ),
+            PriceMode::Expon(decay) => exp_decay(
+                &e,
+                voyage_info.price.clone(),
+                decay.reserve,
+                elapsed,
+                decay.half_life,
+            ),
+        };
+    }
+    let ctrl = get_decayed_ctrl(&e, voyage_id);
+    let ratio = debt_ratio(&e, ctrl.debt, outstanding(&e, get_new_index(&e)));
+    price(&e, ctrl.bcv, ratio, ctrl.floor)
+}
+
+// checks that the voyage is open and has capacity, then returns the current price of a voyage
+fn quote_voyage(
+    e: &Env,
+    voyage_id: i32,
+    voyage_info: &VoyageInfo,
+    num_voyages: BigInt,
+) -> Result<BigInt, SevenSeasError> {
+    check_tracked(&e, voyage_id)?;
+    //check that the voyage is open for embarking
+    if voyage_info.start_ldgr > e.ledger().sequence() {
+        return Err(SevenSeasError::VoyageNotStarted);
+    }
+    if voyage_info.embark_dl < e.ledger().sequence() {
+        return Err(SevenSeasError::VoyageClosed);
+    }
+    if voyage_info.max_vygs.clone() - voyage_info.n_embarked.clone() < num_voyages {
+        return Err(SevenSeasError::NotEnoughVoyages);
+    }
+    Ok(get_voyage_price(&e, voyage_id, &voyage_info))
+}
+
+// checks that the voyage can be embarked on, then takes payment for the voyages from the user and returns it
+fn fund_voyage(
+    e: &Env,
+    voyage_id: i32,
+    user_id: Identifier,
+    voyage_info: &mut VoyageInfo,
+    num_voyages: BigInt,
+    max_price: BigInt,
+) -> Result<BigInt, SevenSeasError> {
+    let voyage_price = quote_voyage(&e, voyage_id, &voyage_info, num_voyages.clone())?;
+    if voyage_price > max_price {
+        return Err(SevenSeasError::PriceAboveMax);
+    }
+    let transfer_amount = voyage_price * num_voyages.clone();
+
+    let voyage_asset_client = crate::token::Client::new(&e, voyage_info.vyg_asset.clone());
+
+    voyage_asset_client.xfer_from(
+        &Signature::Invoker,
+        &BigInt::zero(&e),
+        &user_id,
+        &get_contract_id(&e),
+        &transfer_amount,
+    );
+    add_reserve(&e, voyage_info.vyg_asset.clone(), transfer_amount.clone());
+
+    //the payout sold adds to the offering's debt
+    if has_price_ctrl(&e, voyage_id) {
+        let mut ctrl = get_decayed_ctrl(&e, voyage_id);
+        ctrl.debt = ctrl.debt + num_voyages.clone() * voyage_info.reward.clone();
+        set_price_ctrl(&e, voyage_id, ctrl);
+    }
+
+    //update voyage info
+    voyage_info.n_embarked = voyage_info.n_embarked.clone() + num_voyages;
+    Ok(transfer_amount)
+}
+
+// returns the status of a user's position on a voyage
+fn voyage_status(
+    e: &Env,
+    user_id: Identifier,
+    voyage_id: i32,
+) -> Result<VoyageStatus, SevenSeasError> {
+    let voyage_info = get_voyage(&e, voyage_id)?;
+    let n_vygs = get_user_voyage(&e, user_id, voyage_id);
+    Ok(VoyageStatus {
+        n_vygs: n_vygs.clone(),
+        matures: voyage_info.expiration + 1,
+        mature: voyage_matured(&e, &voyage_info),
+        payout: n_vygs * voyage_info.reward.clone(),
+    })
+}
+
+fn epoch_info(e: &Env) -> EpochInfo {
+    let length = get_epoch_len(&e);
+    let last_block = get_last_block(&e);
+    EpochInfo {
+        number: get_epoch_num(&e),
+        length,
+        next_ldgr: last_block + length,
+        pending: (e.ledger().sequence() - last_block) / length,
+    }
+}
+
+// sums the doubloons waiting in a queue
+fn queued_amount(e: &Env, queue: Vec<QueueEntry>) -> BigInt {
+    let mut total = BigInt::zero(&e);
+    for entry in queue.iter() {
+        total = total + entry.unwrap().amount;
+    }
+    total
+}
+
+// returns the number of voyages a user is on and the doubloons they are paid for ending them now
+fn quote_end_voyage(
+    e: &Env,
+    user_id: Identifier,
+    voyage_id: i32,
+) -> Result<(BigInt, BigInt), SevenSeasError> {
+    let voyage_info = get_voyage(&e, voyage_id)?;
+    check_tracked(&e, voyage_id)?;
+    let user_voyage_amt = get_user_voyage(&e, user_id, voyage_id);
+    if user_voyage_amt == BigInt::zero(&e) {
+        return Err(SevenSeasError::NoVoyages);
+    }
+    // user gets the offering's reward for each voyage they finished
+    let mut mint_amount = user_voyage_amt.clone() * voyage_info.reward.clone();
+    if !voyage_matured(&e, &voyage_info) {
+        let penalty = get_exit_penalty(&e);
+        if penalty >= BigInt::from_i64(&e, SCALER) {
+            return Err(SevenSeasError::VoyageNotCompleted);
+        }
+        mint_amount =
+            mint_amount * (BigInt::from_i64(&e, SCALER) - penalty) / BigInt::from_i64(&e, SCALER);
+    }
+    Ok((user_voyage_amt, mint_amount))
+}
+
+// validates and stores a new voyage offering, returning its id
+fn create_voyage(
+    e: &Env,
+    vyg_asset: BytesN<32>,
+    price: BigInt,
+    max_vygs: BigInt,
+    reward: BigInt,
+    start_ldgr: u32,
+    embark_dl: u32,
+    sail_dur: u32,
+    price_mode: PriceMode,
+) -> Result<i32, SevenSeasError> {
+    if reward <= BigInt::zero(&e) {
+        return Err(SevenSeasError::InvalidReward);
+    }
+    match price_mode.clone() {
+        PriceMode::Fixed => {}
+        PriceMode::Linear(reserve) => {
+            if reserve > price {
+                return Err(SevenSeasError::InvalidReserve);
+            }
+        }
+        PriceMode::Expon(decay) => {
+            if decay.reserve > price {
+                return Err(SevenSeasError::InvalidReserve);
+            }
+            if decay.half_life == 0 {
+                return Err(SevenSeasError::ZeroLength);
+            }
+        }
+    }
+    if start_ldgr < e.ledger().sequence() {
+        return Err(SevenSeasError::InvalidSchedule);
+    }
+    if embark_dl < start_ldgr {
+        return Err(SevenSeasError::InvalidSchedule);
+    }
+    // the voyage has to come home before the ledger runs out of blocks
+    let expiration = embark_dl
+        .checked_add(sail_dur)
+        .ok_or(SevenSeasError::InvalidSchedule)?;
+    let voyage_id = get_last_voyage_id(&e) + 1;
+    let voyage_info = VoyageInfo {
+        vyg_asset,
+        price,
+        max_vygs,
+        n_embarked: BigInt::zero(&e),
+        reward,
+        expiration,
+        start_ldgr,
+        embark_dl,
+        sail_dur,
+        price_mode,
+    };
+    set_voyage(&e, voyage_id, voyage_info);
+    set_voyage_tracked(&e, voyage_id);
+    set_last_voyage_id(&e, voyage_id);
+    Ok(voyage_id)
+}
+
 fn get_contract_id(e: &Env) -> Identifier {
     Identifier::Contract(e.get_current_contract().into())
 }
 
-const WEEK_IN_BLOCKS: u32 = 100_800;
+// number of ledgers a raider has to reveal a commitment once the oracle's randomness arrives
+pub const REVEAL_WINDOW: u32 = 600;
+// number of ledgers the oracle has to answer a randomness request before the raid can be called off
+pub const REQUEST_WINDOW: u32 = 600;
+// number of ledgers a captain handover proposal can be accepted for
+pub const HANDOVER_WINDOW: u32 = WEEK;
 pub struct SevenSeas;
 pub trait SevenSeasTrait {
     #[doc = "
@@ -83,54 +445,389 @@ pub trait SevenSeasTrait {
     - the rate is the per-100-block rebase rate for buried_doubloon tokens
     - the target_raid_interval is the goal number of blocks between raids
     "]
-    fn initialize(e: Env, base_token_id: BytesN<32>, rate: BigInt, target_raid_interval: u32);
+    fn initialize(
+        e: Env,
+        base_token_id: BytesN<32>,
+        rate: BigInt,
+        target_raid_interval: u32,
+    ) -> Result<(), SevenSeasError>;
 
     /******** User functions ********/
     #[doc = "
     Buries doubloons
     - amount is the number of doubloons to be buried
+    - if a warmup is set the doubloons wait in the user's warmup queue and must be claimed with `claim_wrmp`
     "]
-    fn bury(e: Env, amount: BigInt);
+    fn bury(e: Env, amount: BigInt) -> Result<(), SevenSeasError>;
 
     #[doc = "
-    Unearths doubloons
+    Unearths doubloons, only possible while no cooldown is set
     - amount is the number of doubloons to be unearthed
     "]
-    fn unearth(e: Env, amount: BigInt);
+    fn unearth(e: Env, amount: BigInt) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Buries the user's doubloons that have waited out the warmup
+    - returns the number of doubloons buried
+    "]
+    fn claim_wrmp(e: Env) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Buries doubloons under a lock, longer locks earn boosted rebase rewards
+    - amount is the number of doubloons to be buried
+    - weeks is the lock duration, either 1, 4, 12 or 52 weeks for a 1.05x, 1.2x, 1.5x or 2x boost
+    - locked doubloons skip the warmup and can't be transferred, wrapped or unearthed until they are unlocked
+    - fails with LockTooShort if the lock would expire before the warmup, so locks can't be used to dodge it
+    - returns the block the lock expires at
+    "]
+    fn bury_lock(e: Env, amount: BigInt, weeks: u32) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Unlocks an expired lock position, turning it back into buried doubloons
+    - lock_idx is the index of the position in the user's locks, see `get_locks`
+    - returns the number of doubloons unlocked
+    "]
+    fn unlock(e: Env, lock_idx: u32) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Requests to unearth doubloons, they stop earning rebase rewards and can be claimed with `cmp_unrth` after the cooldown
+    - amount is the number of buried doubloon shares to be unearthed
+    - returns the block the doubloons can be claimed at
+    "]
+    fn req_unrth(e: Env, amount: BigInt) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Mints the user's unearthed doubloons that have waited out the cooldown
+    - returns the number of doubloons minted
+    "]
+    fn cmp_unrth(e: Env) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Settles pending rebase epochs, up to MAX_EPOCHS at a time, anyone can call this to keep the index up to date
+    - publishes a rebase event per settled epoch with its number, the doubloons distributed to buried doubloons and the new index
+    - calls that change buried doubloons or rebase parameters fail while more than MAX_EPOCHS epochs are pending, until this clears the backlog
+    - pays the invoker the rebase bounty if any epochs were settled
+    - returns the number of epochs settled
+    "]
+    fn rebase(e: Env) -> u32;
 
     #[doc = "
     Fund and embark on a voyage
     - voyage_id is the id of the voyage to embark on
     - num_voyages is the number of voyages to embark on
+    - max_price is the most the user is willing to pay per voyage, the voyage price can change with the ledger
+    - offerings created before positions were tracked can't be embarked on until the captain repairs them with `fix_vyg`
+    "]
+    fn voyage(
+        e: Env,
+        voyage_id: i32,
+        num_voyages: BigInt,
+        max_price: BigInt,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Buy doubloons from a bond market, the doubloons vest linearly over the market's vesting period
+    - voyage_id is the id of the bond market
+    - num_voyages is the number of bonds to buy, each costs the market price and pays out the market reward
+    - max_price is the most the user is willing to pay per bond, the bond price can change with the ledger
+    - any doubloons already vested from the user's position on the market are claimed and the vesting period restarts
+    "]
+    fn bond(
+        e: Env,
+        voyage_id: i32,
+        num_voyages: BigInt,
+        max_price: BigInt,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Claim the doubloons that have vested from a bond position
+    - voyage_id is the id of the bond market
+    - returns the number of doubloons claimed
     "]
-    fn voyage(e: Env, voyage_id: i32, num_voyages: BigInt);
+    fn claim_vest(e: Env, voyage_id: i32) -> Result<BigInt, SevenSeasError>;
 
     #[doc = "
     End and redeem a voyage
     - voyage_id is the id of the voyage the user wants to end
+    - voyages complete once the offering's expiration block (embark deadline plus sail duration) has passed
+    - ending a voyage before it completes is only allowed if the captain set an early exit penalty below 100%, the penalty is taken from the payout
+    - offerings created before positions were tracked can't be ended until the captain repairs them with `fix_vyg`
     "]
-    fn end_voyage(e: Env, voyage_id: i32);
+    fn end_voyage(e: Env, voyage_id: i32) -> Result<(), SevenSeasError>;
 
     #[doc = "
-    Raid another users voyage
+    Commit to raiding another users voyage, the raid is settled later by `reveal_rd`
     - voyage_id is the id of the voyage the user wants to raid
     - user_id is the id of the user being raided
-    - raider must have enough doubloons to pay for the raid, they need doubloons equal to 1/100th the number of voyages of the input type that the input user is on
+    - hash is the sha256 hash of a secret only known to the raider
+    - raider must have enough doubloons to pay for the raid, they need doubloons equal to 1/100th the payout of the voyages of the input type that the input user is on
+    - needs a randomness oracle, the commitment requests randomness that is mixed with the secret on reveal
+    - the raid cost is burned immediately and is forfeit if the raid is not revealed in time
+    - returns the id of the randomness request, which identifies the commitment
+    - offerings created before positions were tracked can't be raided until the captain repairs them with `fix_vyg`
     "]
-    fn raid(e: Env, voyage_id: i32, user_id: Identifier);
+    fn commit_rd(
+        e: Env,
+        voyage_id: i32,
+        user_id: Identifier,
+        hash: BytesN<32>,
+    ) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Reveal and settle a raid commitment
+    - request_id is the id returned by `commit_rd`
+    - secret is the preimage of the committed hash
+    - can be called once the oracle's randomness arrives, for REVEAL_WINDOW ledgers
+    - the outcome is rolled from the oracle's randomness and the secret, the raider doesn't know the randomness
+      when committing and the oracle never sees the secret, so neither can pick the outcome
+    "]
+    fn reveal_rd(e: Env, request_id: u32, secret: BytesN<32>) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Clears a raid that can no longer be settled, anyone can call this
+    - request_id is the id of the raid's randomness request
+    - a commitment not revealed within REVEAL_WINDOW ledgers of its randomness arriving is forfeit
+    - a raid the oracle hasn't answered within REQUEST_WINDOW ledgers, for example because the oracle was
+      replaced, is called off and its cost is refunded to the raider
+    "]
+    fn expire_rd(e: Env, request_id: u32) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Raid another users voyage using the randomness oracle, the raid is settled when the oracle calls `fulfill_rd`
+    - voyage_id is the id of the voyage the user wants to raid
+    - user_id is the id of the user being raided
+    - raider must have enough doubloons to pay for the raid, they need doubloons equal to 1/100th the payout of the voyages of the input type that the input user is on
+    - returns the id of the randomness request
+    "]
+    fn request_rd(e: Env, voyage_id: i32, user_id: Identifier) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Provides randomness for a raid - can only be called by the oracle contract
+    - request_id is the id of the randomness request
+    - randomness is the random value provided by the oracle
+    - `request_rd` raids are settled right away, `commit_rd` raids wait for the raider to reveal their secret
+    "]
+    fn fulfill_rd(e: Env, request_id: u32, randomness: BytesN<32>) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Accepts a pending captain proposal, making the invoker the captain
+    - only the nominee can accept, and only until the proposal expires
+    - the roles held by the outgoing captain move to the new captain, roles held by others are kept
+    "]
+    fn acpt_capn(e: Env) -> Result<(), SevenSeasError>;
+
+    /******** sDOUBLOON token functions *********/
+    #[doc = "
+    Returns the number of buried doubloons a spender can transfer on behalf of a user
+    - from is the id of the user whose buried doubloons are being spent
+    - spender is the id of the user who can spend them
+    "]
+    fn allowance(e: Env, from: Identifier, spender: Identifier) -> BigInt;
+
+    #[doc = "
+    Allows a spender to transfer some of the invoker's buried doubloons
+    - from is the signature of the user approving the spender
+    - nonce is the user's signature nonce, must be zero when invoked directly
+    - spender is the id of the user who can spend the buried doubloons
+    - amount is the number of buried doubloons the spender can transfer, in doubloon terms
+    "]
+    fn approve(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        spender: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Returns the buried doubloons held by a user in doubloon terms, the balance grows as the index rebases
+    - id is the id of the user whose balance is being queried
+    "]
+    fn balance(e: Env, id: Identifier) -> BigInt;
+
+    #[doc = "
+    Transfers buried doubloons
+    - from is the signature of the user sending the buried doubloons
+    - nonce is the user's signature nonce, must be zero when invoked directly
+    - to is the id of the user receiving the buried doubloons
+    - amount is the number of buried doubloons to transfer, in doubloon terms
+    "]
+    fn xfer(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        to: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Transfers buried doubloons on behalf of another user, using up the spender's allowance
+    - spender is the signature of the user spending the buried doubloons
+    - nonce is the spender's signature nonce, must be zero when invoked directly
+    - from is the id of the user sending the buried doubloons
+    - to is the id of the user receiving the buried doubloons
+    - amount is the number of buried doubloons to transfer, in doubloon terms
+    "]
+    fn xfer_from(
+        e: Env,
+        spender: Signature,
+        nonce: BigInt,
+        from: Identifier,
+        to: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError>;
 
-    /******** Read Functions *********/
     #[doc = "
     Returns number of decimals associated with buried doubloons and the doubloon rebase rate
     "]
-    fn decimals(e: Env) -> BigInt;
+    fn decimals(e: Env) -> u32;
+
+    #[doc = "
+    Returns the name of the buried doubloon token
+    "]
+    fn name(e: Env) -> Bytes;
+
+    #[doc = "
+    Returns the symbol of the buried doubloon token
+    "]
+    fn symbol(e: Env) -> Bytes;
+
+    #[doc = "
+    Returns the next signature nonce for a user
+    - id is the id of the user
+    "]
+    fn nonce(e: Env, id: Identifier) -> BigInt;
+
+    #[doc = "
+    Wraps buried doubloons into wsDOUBLOON, a fixed balance token whose value rises with the index
+    - amount is the number of buried doubloons to wrap, in doubloon terms
+    - returns the number of wsDOUBLOON minted, one per buried share
+    "]
+    fn wrap(e: Env, amount: BigInt) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Unwraps wsDOUBLOON back into buried doubloons
+    - amount is the number of wsDOUBLOON to unwrap
+    - returns the number of buried doubloons received, in doubloon terms
+    "]
+    fn unwrap(e: Env, amount: BigInt) -> Result<BigInt, SevenSeasError>;
+
+    /******** Read Functions *********/
+    #[doc = "
+    Returns the current rebase index, the number of doubloons a buried share or wsDOUBLOON is worth scaled by twice the contract decimals
+    "]
+    fn get_index(e: Env) -> BigInt;
+
+    #[doc = "
+    Returns information about the rebase epoch
+    - will return a struct with the following fields:
+        - number: number of epochs settled
+        - length: number of blocks in an epoch
+        - next_ldgr: block the next epoch can be settled at
+        - pending: number of epochs that can be settled now
+    "]
+    fn epoch(e: Env) -> EpochInfo;
+
+    #[doc = "
+    Returns the global state of the protocol in a single call
+    - will return a struct with the following fields:
+        - base_token: address of the doubloon token
+        - captain: address of the captain
+        - decimals: decimals for the index, rate and buried doubloons
+        - rate: per epoch rebase rate, scaled by the contract decimals
+        - index: rebase index projected to the current block, scaled by twice the contract decimals
+        - last_ldgr: block the stored index was last updated at
+        - epoch: rebase epoch information, see `epoch`
+        - bounty: doubloons paid to the caller of `rebase`
+        - ttl_buried: total buried doubloon shares, including the boosted shares of locks
+        - ttl_queued: doubloons waiting in warmup and cooldown queues
+        - supply: doubloons minted by the sea less those it burned
+        - excess_rsv: treasury reserves in excess of the doubloons the sea owes, see `excess_rsv`
+        - warmup: number of blocks newly buried doubloons wait before they can be claimed
+        - cooldown: number of blocks unearthed doubloons wait before they can be claimed
+        - exit_pen: penalty taken from voyages ended before they complete, scaled by the contract decimals
+        - tgt_raid: target number of blocks between raids
+        - last_raid: last block a raid occurred on
+        - last_vyg: id of the last voyage offering
+    "]
+    fn prot_state(e: Env) -> ProtocolState;
+
+    #[doc = "
+    Returns the state of a user's positions in a single call
+    - user_id is the id of the user being queried
+    - will return a struct with the following fields:
+        - doubloons: the user's doubloon balance
+        - buried: the user's buried doubloon shares
+        - value: value of the buried shares in doubloons at the current index
+        - locked: value of the user's lock positions in doubloons at the current index
+        - queued: doubloons waiting in the user's warmup and cooldown queues
+        - voyages: status of every voyage the user has an open position on, by voyage id, see `vyg_status` - positions older than the position list show up once the user embarks on the voyage again
+    "]
+    fn user_state(e: Env, user_id: Identifier) -> UserState;
+
+    #[doc = "
+    Returns the treasury reserves in excess of the doubloons the sea owes, valued in doubloons - this can be negative
+    "]
+    fn excess_rsv(e: Env) -> BigInt;
+
+    #[doc = "
+    Returns the number of epochs the current rate can be paid out of excess reserves
+    "]
+    fn runway(e: Env) -> u32;
 
     #[doc = "
-    Returns the number of doubloons buried by the input user
+    Returns the number of wsDOUBLOON that wrapping an amount of buried doubloons would mint
+    - amount is the number of buried doubloons, in doubloon terms
+    "]
+    fn wrap_prvw(e: Env, amount: BigInt) -> BigInt;
+
+    #[doc = "
+    Returns the number of buried doubloons, in doubloon terms, that unwrapping an amount of wsDOUBLOON would return
+    - amount is the number of wsDOUBLOON
+    "]
+    fn unwrp_prvw(e: Env, amount: BigInt) -> BigInt;
+
+    #[doc = "
+    Returns the buried doubloon shares held by the input user, see `balance` for the value in doubloons
     - user_id is the id of the user whose buried doubloons are being queried
     "]
     fn get_buried(e: Env, user_id: Identifier) -> BigInt;
 
+    #[doc = "
+    Returns the input user's warmup queue
+    - user_id is the id of the user whose queue is being queried
+    - will return a vec of structs with the following fields:
+        - amount: doubloons waiting in the queue
+        - ready: first block the entry can be claimed
+    "]
+    fn get_wrmp(e: Env, user_id: Identifier) -> Vec<QueueEntry>;
+
+    #[doc = "
+    Returns the input user's lock positions
+    - user_id is the id of the user whose locks are being queried
+    - will return a vec of structs with the following fields:
+        - amount: doubloons buried under the lock
+        - shares: boosted buried shares held by the lock
+        - boost: rebase reward boost, scaled by the contract decimals
+        - unlock: first block the lock can be unlocked
+    "]
+    fn get_locks(e: Env, user_id: Identifier) -> Vec<LockPosition>;
+
+    #[doc = "
+    Returns the input user's rebase earning shares per unboosted share, scaled by the contract decimals
+    - user_id is the id of the user whose boost is being queried
+    "]
+    fn boost_of(e: Env, user_id: Identifier) -> BigInt;
+
+    #[doc = "
+    Returns the input user's cooldown queue
+    - user_id is the id of the user whose queue is being queried
+    - will return a vec of structs with the following fields:
+        - amount: doubloons waiting in the queue
+        - ready: first block the entry can be claimed
+    "]
+    fn get_cool(e: Env, user_id: Identifier) -> Vec<QueueEntry>;
+
     #[doc = "
     Returns information about the input voyage
     - voyage_id is the id of the voyage being queried
@@ -139,9 +836,14 @@ pub trait SevenSeasTrait {
         - price: the cost to embark on a voyage in voyage asset
         - max_vygs: the maximum number of voyages that can be embarked on for this voyage offering
         - n_embarked: number of voyages that have been embarked on
-        - expiration: block the voyage expires on
+        - reward: doubloons paid out per completed voyage
+        - expiration: last block the voyage is at sea, voyages complete after it
+        - start_ldgr: first block the voyage can be embarked on
+        - embark_dl: last block the voyage can be embarked on
+        - sail_dur: number of blocks voyages sail for after the embark deadline
+    - fails with NoVoyage if no offering has the id
     "]
-    fn get_voyage(e: Env, voyage_id: i32) -> VoyageInfo;
+    fn get_voyage(e: Env, voyage_id: i32) -> Result<VoyageInfo, SevenSeasError>;
 
     #[doc = "
     Returns the number of voyages for a specific offering embarked on by the input user
@@ -150,19 +852,175 @@ pub trait SevenSeasTrait {
     "]
     fn get_u_vygs(e: Env, user_id: Identifier, voyage_id: i32) -> BigInt;
 
+    #[doc = "
+    Returns the maturity status of a user's position on a voyage offering
+    - user_id is the id of the user whose position is being queried
+    - voyage_id is the id of the voyage offering being queried
+    - will return a struct with the following fields:
+        - n_vygs: number of voyages the user is on
+        - matures: first block the position can be redeemed without penalty
+        - mature: whether the position can be redeemed without penalty
+        - payout: doubloons paid out for the position once it completes
+    "]
+    fn vyg_status(
+        e: Env,
+        user_id: Identifier,
+        voyage_id: i32,
+    ) -> Result<VoyageStatus, SevenSeasError>;
+
+    #[doc = "
+    Returns a user's vesting position on a bond market
+    - user_id is the id of the user whose position is being queried
+    - voyage_id is the id of the bond market
+    - will return a struct with the following fields:
+        - payout: doubloons left to be paid out
+        - vesting: blocks left until the position is fully vested, as of last_ldgr
+        - last_ldgr: block the position was last claimed or added to
+        - claimable: doubloons that can be claimed now
+    "]
+    fn bond_info(e: Env, user_id: Identifier, voyage_id: i32) -> BondInfo;
+
+    #[doc = "
+    Returns the current price of a voyage in its voyage asset
+    - voyage_id is the id of the voyage offering
+    - offerings with a price control are priced off their outstanding debt, otherwise the fixed offering price is returned
+    "]
+    fn cur_price(e: Env, voyage_id: i32) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Returns whether the positions of the users on a voyage and its closed voyages add up to the number of voyages embarked on
+    - voyage_id is the id of the voyage offering being checked
+    "]
+    fn recon_vyg(e: Env, voyage_id: i32) -> Result<bool, SevenSeasError>;
+
     #[doc = "
     Returns the last block a raid ocurred on   
     "]
     fn get_l_raid(e: Env) -> u32;
 
+    #[doc = "
+    Returns an outstanding raid commitment
+    - request_id is the id returned by `commit_rd`
+    "]
+    fn get_rd_cmt(e: Env, request_id: u32) -> Result<RaidCommit, SevenSeasError>;
+
+    #[doc = "
+    Returns a raid waiting on randomness from the oracle
+    - request_id is the id of the randomness request
+    "]
+    fn get_rd_req(e: Env, request_id: u32) -> Result<RaidRequest, SevenSeasError>;
+
+    #[doc = "
+    Returns the pending captain proposal
+    "]
+    fn capn_prop(e: Env) -> Result<CapnProposal, SevenSeasError>;
+
+    #[doc = "
+    Returns whether an identity holds a role
+    - role is one of VygMaster, Treasurer, RateKeeper or Guardian, the captain is the only one who can grant them
+    - id is the identity being checked
+    "]
+    fn has_role(e: Env, role: Role, id: Identifier) -> bool;
+
+    #[doc = "
+    Returns the council's members and approval threshold, fails with NotCouncil if the council isn't the captain
+    "]
+    fn council(e: Env) -> Result<Council, SevenSeasError>;
+
+    #[doc = "
+    Returns an admin action proposed to the council that hasn't been executed
+    - action_id is the id of the proposed action
+    "]
+    fn get_action(e: Env, action_id: u32) -> Result<ActionProp, SevenSeasError>;
+
+    /******** Previews *********/
+    #[doc = "
+    Returns the number of buried doubloon shares burying an amount of doubloons would buy now
+    - amount is the number of doubloons to be buried
+    - if a warmup is set the doubloons are queued instead, and the shares are bought at the index when they are claimed
+    "]
+    fn prv_bury(e: Env, amount: BigInt) -> BigInt;
+
+    #[doc = "
+    Returns the number of doubloons unearthing an amount of buried doubloon shares would pay now
+    - amount is the number of buried doubloon shares to be unearthed
+    "]
+    fn prv_unrth(e: Env, amount: BigInt) -> BigInt;
+
+    #[doc = "
+    Returns the cost in voyage asset of embarking on a number of voyages, or buying a number of bonds, now
+    - voyage_id is the id of the offering
+    - num_voyages is the number of voyages to embark on
+    - returns the error embarking would if the offering can't be embarked on now
+    "]
+    fn prv_vyg(e: Env, voyage_id: i32, num_voyages: BigInt) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Returns the doubloons a user would be paid for ending their voyages now, after any early exit penalty
+    - user_id is the id of the user on the voyage
+    - voyage_id is the id of the voyage
+    - returns the error `end_voyage` would if the user can't end the voyage now
+    "]
+    fn prv_end_v(e: Env, user_id: Identifier, voyage_id: i32) -> Result<BigInt, SevenSeasError>;
+
+    #[doc = "
+    Returns a quote for raiding a user's voyage now
+    - voyage_id is the id of the voyage being raided
+    - user_id is the id of the user being raided
+    - will return a struct with the following fields:
+        - n_vygs: number of voyages that would be raided
+        - cost: doubloons burned from the raider to pay for the raid
+        - odds: raid probability (scaled by 10000) that would be locked in
+    - returns the error a raid would if the voyage can't be raided now
+    "]
+    fn raid_odds(e: Env, voyage_id: i32, user_id: Identifier) -> Result<RaidQuote, SevenSeasError>;
+
     /******** Captain only functions ********/
     #[doc = "
     Creates a new voyage offering  
     - voyage_asset is the asset used to fund the voyage
     - price is the cost to embark on a voyage in voyage asset
     - available_voyages is the maximum number of voyages that can be embarked on for this voyage offering  
+    - reward_per_voyage is the number of doubloons paid out per completed voyage, scaled by the contract decimals
+    - start_ledger is the first block the voyage can be embarked on, it can't be in the past
+    - embark_deadline is the last block the voyage can be embarked on, it can't be before the start ledger
+    - sail_duration is the number of blocks voyages sail for after the embark deadline before they complete, it must be positive so no voyage completes the block after it was embarked on, and embark_deadline plus sail_duration can't overflow the ledger sequence
+    - price_mode is how the price decays over the embark window, dutch auctions start at price and decay towards a reserve price
+    "]
+    fn new_voyage(
+        e: Env,
+        voyage_asset: BytesN<32>,
+        price: BigInt,
+        available_voyages: BigInt,
+        reward_per_voyage: BigInt,
+        start_ledger: u32,
+        embark_deadline: u32,
+        sail_duration: u32,
+        price_mode: PriceMode,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Creates a new bond market, where users buy doubloons at a discount that vest over time
+    - voyage_asset is the treasury asset used to buy bonds
+    - price is the cost of a bond in the treasury asset
+    - available_voyages is the maximum number of bonds that can be bought
+    - reward_per_voyage is the number of doubloons paid out per bond, scaled by the contract decimals - the discount is set by pricing this above the market value of price
+    - start_ledger is the first block bonds can be bought, it can't be in the past
+    - embark_deadline is the last block bonds can be bought, it can't be before the start ledger
+    - vesting_length is the number of blocks bond payouts vest over
+    - price_mode is how the price decays over the embark window, dutch auctions start at price and decay towards a reserve price
     "]
-    fn new_voyage(e: Env, voyage_asset: BytesN<32>, price: BigInt, available_voyages: BigInt);
+    fn new_bond(
+        e: Env,
+        voyage_asset: BytesN<32>,
+        price: BigInt,
+        available_voyages: BigInt,
+        reward_per_voyage: BigInt,
+        start_ledger: u32,
+        embark_deadline: u32,
+        vesting_length: u32,
+        price_mode: PriceMode,
+    ) -> Result<(), SevenSeasError>;
 
     #[doc = "
     Transfers funds held in the contract
@@ -170,171 +1028,1023 @@ pub trait SevenSeasTrait {
     - to is the destination for the transfer
     - amount is the amount of tokesn to transfer
     "]
-    fn xfer_held(e: Env, token_id: BytesN<32>, to: Identifier, amount: BigInt);
+    fn xfer_held(
+        e: Env,
+        token_id: BytesN<32>,
+        to: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError>;
 
     #[doc = "
     Sets the rebase rate for buried doubloons (the rate at which doubloons grow when buried)
-    - rate is the per 100 block rebase rate for buried doubloons
+    - rate is the per epoch rebase rate for buried doubloons, scaled by the contract decimals
+    - the rewards actually paid each epoch are capped at the reward cap fraction of the treasury's excess reserves, see `runway`
     "]
-    fn set_rate(e: Env, rate: BigInt);
+    fn set_rate(e: Env, rate: BigInt) -> Result<(), SevenSeasError>;
 
     #[doc = "
-    Sets a new captain for the Seven Seas protocol
-    - new_captain is the address of the new captain
+    Sets the rebase epoch length, pending epochs are settled at the old length first
+    - epoch_len is the number of blocks in an epoch, the rate is the growth per epoch
     "]
-    fn set_capn(e: Env, new_captain: Identifier);
+    fn set_epoch(e: Env, epoch_len: u32) -> Result<(), SevenSeasError>;
 
     #[doc = "
-    Sets the target raid interval (how often raids should occur)
-    - tgt_raid_int is the target number of blocks between raids
+    Sets the bounty paid to callers of `rebase`
+    - bounty is the number of doubloons paid each time `rebase` settles epochs
     "]
-    fn set_tgt_ri(e: Env, tgt_raid_int: u32);
-}
+    fn set_bounty(e: Env, bounty: BigInt) -> Result<(), SevenSeasError>;
 
-// ****** Contract ******
+    #[doc = "
+    Sets the value of a voyage asset held as treasury reserves
+    - asset is the address of the voyage asset
+    - value is the number of doubloons one unit of the asset is worth, scaled by the contract decimals
+    "]
+    fn set_rfv(e: Env, asset: BytesN<32>, value: BigInt) -> Result<(), SevenSeasError>;
 
-#[contractimpl]
-impl SevenSeasTrait for SevenSeas {
-    fn initialize(e: Env, base_token_id: BytesN<32>, rate: BigInt, target_raid_interval: u32) {
-        if e.data().has(DataKey::BaseToken) {
-            panic!("contract already initialized");
-        }
-        //check if PiratesBay contract is the admin for base tokens and share token
-        /*** Note - currently not possible as you can't read token admins TODO: file issue */
+    #[doc = "
+    Caps the rebase rewards paid each epoch at a fraction of the treasury's excess reserves
+    - cap is the fraction of excess reserves, scaled by the contract decimals, the cap is 100% until it is set
+    "]
+    fn set_rwdcap(e: Env, cap: BigInt) -> Result<(), SevenSeasError>;
 
-        set_base_token(&e, base_token_id);
+    #[doc = "
+    Sets the warmup for newly buried doubloons, doubloons already queued keep their claim block
+    - blocks is the number of blocks buried doubloons wait before they can be claimed, zero buries immediately
+    "]
+    fn set_warmup(e: Env, blocks: u32) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Sets the cooldown for unearthing doubloons, doubloons already queued keep their claim block
+    - blocks is the number of blocks unearthed doubloons wait before they can be claimed
+    - while no cooldown is set doubloons can be unearthed immediately with `unearth`
+    "]
+    fn set_cooldn(e: Env, blocks: u32) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Proposes a new captain for the Seven Seas protocol, the nominee has to accept with `acpt_capn`
+    - nominee is the address of the proposed captain
+    - replaces any pending proposal
+    - returns the last block the proposal can be accepted on
+    "]
+    fn prop_capn(e: Env, nominee: Identifier) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Cancels the pending captain proposal
+    "]
+    fn cncl_capn(e: Env) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Gives up the captaincy for good, no one can call captain only functions afterwards
+    - cancels any pending captain proposal
+    - revokes every role, no one can use role gated functions afterwards
+    "]
+    fn rnce_capn(e: Env) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Sets the target raid interval (how often raids should occur)
+    - tgt_raid_int is the target number of blocks between raids
+    "]
+    fn set_tgt_ri(e: Env, tgt_raid_int: u32) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Repairs a voyage offering whose positions were overwritten by repeat embarks - a one time migration for offerings created before positions were tracked, which can't be embarked on, ended or raided until they are repaired
+    - voyage_id is the id of the voyage offering being repaired
+    - positions are the correct open positions of every user on the voyage, rebuilt from the voyage transaction history, users named with zero have their position deleted
+    - closed is the number of voyages on the offering that were already redeemed or plundered
+    "]
+    fn fix_vyg(
+        e: Env,
+        voyage_id: i32,
+        positions: Map<Identifier, BigInt>,
+        closed: BigInt,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Sets the penalty taken from the payout of voyages ended before they complete
+    - penalty is the fraction of the payout forfeited, scaled by the contract decimals - a penalty of 100% (the default) refuses early exits
+    "]
+    fn set_exit_p(e: Env, penalty: BigInt) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Sets the price control for a voyage or bond offering, once set the offering is priced off its outstanding debt instead of its fixed price
+    - voyage_id is the id of the offering
+    - bcv is the control variable the debt ratio is multiplied by to get the price, it is the price in the voyage asset at a debt ratio of 100%
+    - floor is the minimum price of a voyage in the voyage asset
+    - decay_len is the number of blocks the offering's debt decays to zero over
+    - existing debt on the offering is kept
+    "]
+    fn set_ctrl(
+        e: Env,
+        voyage_id: i32,
+        bcv: BigInt,
+        floor: BigInt,
+        decay_len: u32,
+    ) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Sets the randomness oracle used to settle raids, raids can't be made until one is set
+    - oracle_id is the address of the oracle contract
+    "]
+    fn set_oracle(e: Env, oracle_id: BytesN<32>) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Sets the wsDOUBLOON token, the Seven Seas contract must be the token admin
+    - token_id is the address of the wrapped token contract
+    "]
+    fn set_wrap(e: Env, token_id: BytesN<32>) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Grants a role to an identity, an identity can hold any number of roles
+    - role is the role being granted
+        - VygMaster: creates voyages and bond markets with `new_voyage` and `new_bond`
+        - Treasurer: moves treasury holdings with `xfer_held`
+        - RateKeeper: sets the rebase rate and target raid interval with `set_rate` and `set_tgt_ri`
+        - Guardian: pauses and unpauses the sea
+    - id is the identity receiving the role
+    "]
+    fn grant_role(e: Env, role: Role, id: Identifier) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Revokes a role from an identity, revoking a role the identity doesn't hold does nothing
+    - role is the role being revoked
+    - id is the identity losing the role
+    "]
+    fn rvke_role(e: Env, role: Role, id: Identifier) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Hands the captaincy to an M-of-N council, captain only functions can then only be run through council proposals
+    - members are the addresses on the council, without duplicates
+    - threshold is the number of member approvals an action needs before it can be executed
+    - the council can change its members and threshold with a SetCouncil action, and hand the captaincy to a single address with PropCapn
+    - every role the outgoing captain holds is revoked, the council can grant roles with GrantRole actions
+    "]
+    fn set_cncl(e: Env, members: Vec<Identifier>, threshold: u32) -> Result<(), SevenSeasError>;
+
+    /******** Guardian functions ********/
+    #[doc = "
+    Pauses the sea, no one can bury, lock, embark, bond or raid until it is unpaused
+    - users can still unearth, unlock and end their voyages while the sea is paused
+    "]
+    fn pause(e: Env) -> Result<(), SevenSeasError>;
+
+    #[doc = "
+    Unpauses the sea
+    "]
+    fn unpause(e: Env) -> Result<(), SevenSeasError>;
+
+    /******** Council functions ********/
+    #[doc = "
+    Proposes an admin action to the council, the proposer's approval is counted
+    - action is the captain only call to make, one AdminAction variant per captain only function
+    - the proposal can be approved and executed for HANDOVER_WINDOW blocks
+    - returns the id of the proposed action
+    "]
+    fn prop_act(e: Env, action: AdminAction) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Approves an admin action proposed to the council
+    - action_id is the id of the proposed action
+    - returns the number of current members that have approved the action
+    "]
+    fn aprv_act(e: Env, action_id: u32) -> Result<u32, SevenSeasError>;
+
+    #[doc = "
+    Executes an admin action once enough current council members have approved it
+    - action_id is the id of the proposed action
+    - approvals from addresses that have since left the council don't count
+    "]
+    fn exec_act(e: Env, action_id: u32) -> Result<(), SevenSeasError>;
+}
+
+// makes a captain only change, the caller checks that it was authorized by the captain or the council
+fn run_action(e: &Env, action: AdminAction) -> Result<(), SevenSeasError> {
+    match action {
+        AdminAction::SetEpoch(epoch_len) => {
+            if epoch_len == 0 {
+                return Err(SevenSeasError::ZeroLength);
+            }
+            settle_pending(&e)?;
+            set_epoch_len(&e, epoch_len);
+            captain_event(&e, symbol!("set_epoch"), epoch_len);
+        }
+        AdminAction::SetBounty(bounty) => {
+            if bounty < BigInt::zero(&e) {
+                return Err(SevenSeasError::NegativeAmount);
+            }
+            set_bounty(&e, bounty.clone());
+            captain_event(&e, symbol!("set_bounty"), bounty);
+        }
+        AdminAction::SetRfv(RfvArgs { asset, value }) => {
+            if value < BigInt::zero(&e) {
+                return Err(SevenSeasError::NegativeAmount);
+            }
+            settle_pending(&e)?;
+            set_rfv(&e, asset.clone(), value.clone());
+            captain_event(&e, symbol!("set_rfv"), (asset, value));
+        }
+        AdminAction::SetRwdCap(cap) => {
+            if cap < BigInt::zero(&e) || cap > BigInt::from_i64(&e, SCALER) {
+                return Err(SevenSeasError::OutOfRange);
+            }
+            settle_pending(&e)?;
+            set_reward_cap(&e, cap.clone());
+            captain_event(&e, symbol!("set_rwdcap"), cap);
+        }
+        AdminAction::SetWarmup(blocks) => {
+            set_warmup(&e, blocks);
+            captain_event(&e, symbol!("set_warmup"), blocks);
+        }
+        AdminAction::SetCooldn(blocks) => {
+            set_cooldown(&e, blocks);
+            captain_event(&e, symbol!("set_cooldn"), blocks);
+        }
+        AdminAction::FixVyg(FixArgs {
+            voyage_id,
+            positions,
+            closed,
+        }) => {
+            // offerings that track their positions were never affected, and can only be repaired once
+            if is_voyage_tracked(&e, voyage_id) {
+                return Err(SevenSeasError::AlreadyTracked);
+            }
+            for position in positions.iter() {
+                let (user, amount) = position.unwrap();
+                // rewriting every named position also lists its user on the offering
+                remove_user_voyage(&e, user.clone(), voyage_id);
+                if amount > BigInt::zero(&e) {
+                    set_user_voyage(&e, user, voyage_id, amount);
+                }
+            }
+            set_voyage_closed(&e, voyage_id, closed.clone());
+            if !voyage_reconciles(&e, voyage_id)? {
+                return Err(SevenSeasError::DoesNotReconcile);
+            }
+            set_voyage_tracked(&e, voyage_id);
+            voyage_event(
+                &e,
+                symbol!("fix_vyg"),
+                read_captain(&e),
+                voyage_id,
+                (positions, closed),
+            );
+        }
+        AdminAction::SetExitP(penalty) => {
+            if penalty < BigInt::zero(&e) || penalty > BigInt::from_i64(&e, SCALER) {
+                return Err(SevenSeasError::OutOfRange);
+            }
+            set_exit_penalty(&e, penalty.clone());
+            captain_event(&e, symbol!("set_exit_p"), penalty);
+        }
+        AdminAction::SetCtrl(CtrlArgs {
+            voyage_id,
+            bcv,
+            floor,
+            decay_len,
+        }) => {
+            // make sure the offering exists
+            get_voyage(&e, voyage_id)?;
+            if decay_len == 0 {
+                return Err(SevenSeasError::ZeroLength);
+            }
+            let debt = if has_price_ctrl(&e, voyage_id) {
+                get_decayed_ctrl(&e, voyage_id).debt
+            } else {
+                BigInt::zero(&e)
+            };
+            set_price_ctrl(
+                &e,
+                voyage_id,
+                PriceCtrl {
+                    bcv: bcv.clone(),
+                    floor: floor.clone(),
+                    debt,
+                    decay_len,
+                    last_decay: e.ledger().sequence(),
+                },
+            );
+            voyage_event(
+                &e,
+                symbol!("set_ctrl"),
+                read_captain(&e),
+                voyage_id,
+                (bcv, floor, decay_len),
+            );
+        }
+        AdminAction::SetOracle(oracle_id) => {
+            set_oracle(&e, oracle_id.clone());
+            captain_event(&e, symbol!("set_oracle"), oracle_id);
+        }
+        AdminAction::SetWrap(token_id) => {
+            set_wrapped(&e, token_id.clone());
+            captain_event(&e, symbol!("set_wrap"), token_id);
+        }
+        AdminAction::PropCapn(nominee) => {
+            let expires = write_proposal(&e, nominee.clone());
+            captain_event(&e, symbol!("prop_capn"), (nominee, expires));
+        }
+        AdminAction::CnclCapn => {
+            let proposal = read_proposal(&e)?;
+            remove_proposal(&e);
+            captain_event(&e, symbol!("cncl_capn"), proposal.nominee);
+        }
+        AdminAction::RnceCapn => {
+            remove_proposal(&e);
+            remove_council(&e);
+            // roles are handed out by the captain, so they can't outlive the captaincy
+            for key in read_roles(&e).iter() {
+                let RoleKey { role, id } = key.unwrap();
+                revoke(&e, role, id);
+            }
+            // the sea never invokes itself, so handing it the captaincy leaves no one able to use it
+            let sea_id = get_contract_id(&e);
+            captain_event(&e, symbol!("rnce_capn"), sea_id.clone());
+            write_captain(&e, sea_id);
+        }
+        AdminAction::GrantRole(RoleKey { role, id }) => grant(&e, role, id),
+        AdminAction::RvkeRole(RoleKey { role, id }) => revoke(&e, role, id),
+        AdminAction::SetCouncil(council) => {
+            if council.threshold == 0 || council.threshold > council.members.len() {
+                return Err(SevenSeasError::InvalidCouncil);
+            }
+            for (i, member) in council.members.iter().enumerate() {
+                let member = member.unwrap();
+                if council.members.first_index_of(&member) != Some(i as u32) {
+                    return Err(SevenSeasError::InvalidCouncil);
+                }
+            }
+            // the outgoing captain's roles would let them keep acting without the council
+            revoke_all(&e, read_captain(&e));
+            // the council acts through the sea, so the sea becomes the captain
+            let sea_id = get_contract_id(&e);
+            write_council(&e, council.clone());
+            captain_event(&e, symbol!("set_cncl"), (council, sea_id.clone()));
+            write_captain(&e, sea_id);
+        }
+    }
+    Ok(())
+}
+
+// ****** Contract ******
+
+#[contractimpl]
+impl SevenSeasTrait for SevenSeas {
+    fn initialize(
+        e: Env,
+        base_token_id: BytesN<32>,
+        rate: BigInt,
+        target_raid_interval: u32,
+    ) -> Result<(), SevenSeasError> {
+        if e.data().has(DataKey::BaseToken) {
+            return Err(SevenSeasError::AlreadyInitialized);
+        }
+        //check if PiratesBay contract is the admin for base tokens and share token
+        /*** Note - currently not possible as you can't read token admins TODO: file issue */
+
+        set_base_token(&e, base_token_id);
         set_total_buried(&e, BigInt::zero(&e));
         set_rate(&e, rate);
         // we double the scale of stored indexes to ensure that we don't run into decimal issues
         set_index(&e, BigInt::from_i64(&e, SCALER * SCALER));
-        set_last_block(&e);
+        set_last_block(&e, e.ledger().sequence());
         set_decimals(&e);
         set_target_raid_interval(&e, target_raid_interval);
         write_captain(&e, Identifier::from(e.invoker()));
+        captain_event(
+            &e,
+            symbol!("init"),
+            (get_base_token(&e), get_rate(&e), target_raid_interval),
+        );
+        // the first captain holds every role until they hand them out
+        for role in ROLES {
+            grant(&e, role, read_captain(&e));
+        }
+        Ok(())
     }
 
-    fn bury(e: Env, amount: BigInt) {
+    fn bury(e: Env, amount: BigInt) -> Result<(), SevenSeasError> {
+        check_unpaused(&e)?;
         let user_id = Identifier::from(e.invoker());
-        let new_index = get_new_index(&e);
-        set_index(&e, new_index.clone());
-        set_last_block(&e);
+        settle_pending(&e)?;
+        let new_index = get_index(&e);
         burn_token(&e, user_id.clone(), amount.clone());
-        let bury_amount = amount * BigInt::from_i64(&e, SCALER * SCALER) / new_index.clone();
-        add_buried(&e, user_id, bury_amount);
+        let warmup = get_warmup(&e);
+        if warmup > 0 {
+            let ready = queue_warmup(&e, user_id.clone(), amount.clone(), warmup);
+            user_event(&e, symbol!("warmup"), user_id, (amount, ready));
+            return Ok(());
+        }
+        let shares = shares_at(&e, amount.clone(), new_index);
+        add_buried(&e, user_id.clone(), shares.clone());
+        user_event(&e, symbol!("bury"), user_id, (amount, shares));
+        Ok(())
     }
 
-    fn unearth(e: Env, amount: BigInt) {
+    fn unearth(e: Env, amount: BigInt) -> Result<(), SevenSeasError> {
+        if get_cooldown(&e) > 0 {
+            return Err(SevenSeasError::CooldownActive);
+        }
         let user = Identifier::from(e.invoker());
-        let new_index = get_new_index(&e);
-        set_index(&e, new_index.clone());
-        set_last_block(&e);
-        subtract_buried(&e, user.clone(), amount.clone());
-        let mint_amount = amount * new_index / BigInt::from_i64(&e, SCALER * SCALER);
-        mint_token(&e, user, mint_amount);
+        settle_pending(&e)?;
+        let new_index = get_index(&e);
+        subtract_buried(&e, user.clone(), amount.clone())?;
+        let unearth_amount = doubloons_at(&e, amount.clone(), new_index);
+        mint_token(&e, user.clone(), unearth_amount.clone());
+        user_event(&e, symbol!("unearth"), user, (amount, unearth_amount));
+        Ok(())
     }
 
-    /// Requires approval for `transfer_from` before running
-    fn voyage(e: Env, voyage_id: i32, num_voyages: BigInt) {
-        let mut voyage_info = get_voyage(&e, voyage_id);
+    fn claim_wrmp(e: Env) -> Result<BigInt, SevenSeasError> {
+        let user_id = Identifier::from(e.invoker());
+        settle_pending(&e)?;
+        let new_index = get_index(&e);
+        let amount = claim_warmup(&e, user_id.clone())?;
+        let shares = shares_at(&e, amount.clone(), new_index);
+        add_buried(&e, user_id.clone(), shares.clone());
+        user_event(&e, symbol!("claim_wrmp"), user_id, (amount.clone(), shares));
+        Ok(amount)
+    }
+
+    fn bury_lock(e: Env, amount: BigInt, weeks: u32) -> Result<u32, SevenSeasError> {
+        check_unpaused(&e)?;
+        // a lock shorter than the warmup would start earning sooner than plain buried doubloons
+        if (weeks as u64) * (WEEK as u64) < get_warmup(&e) as u64 {
+            return Err(SevenSeasError::LockTooShort);
+        }
+        let user_id = Identifier::from(e.invoker());
+        settle_pending(&e)?;
+        let new_index = get_index(&e);
+        burn_token(&e, user_id.clone(), amount.clone());
+        add_lock(&e, user_id, amount, weeks, new_index)
+    }
+
+    fn unlock(e: Env, lock_idx: u32) -> Result<BigInt, SevenSeasError> {
+        let user_id = Identifier::from(e.invoker());
+        settle_pending(&e)?;
+        let new_index = get_index(&e);
+        let amount = remove_lock(&e, user_id.clone(), lock_idx, new_index.clone())?;
+        let shares = shares_at(&e, amount.clone(), new_index);
+        add_buried(&e, user_id.clone(), shares.clone());
+        user_event(
+            &e,
+            symbol!("unlock"),
+            user_id,
+            (lock_idx, amount.clone(), shares),
+        );
+        Ok(amount)
+    }
+
+    fn req_unrth(e: Env, amount: BigInt) -> Result<u32, SevenSeasError> {
+        let user = Identifier::from(e.invoker());
+        settle_pending(&e)?;
+        let new_index = get_index(&e);
+        subtract_buried(&e, user.clone(), amount.clone())?;
+        let unearth_amount = doubloons_at(&e, amount.clone(), new_index);
+        let ready = queue_cooldown(&e, user.clone(), unearth_amount.clone(), get_cooldown(&e));
+        user_event(
+            &e,
+            symbol!("cooldown"),
+            user,
+            (amount, unearth_amount, ready),
+        );
+        Ok(ready)
+    }
 
-        //check that the voyage is still available
-        if voyage_info.expiration.clone() < e.ledger().sequence().into() {
-            panic!("voyage no longer available");
+    fn cmp_unrth(e: Env) -> Result<BigInt, SevenSeasError> {
+        let user = Identifier::from(e.invoker());
+        let amount = claim_cooldown(&e, user.clone())?;
+        mint_token(&e, user.clone(), amount.clone());
+        user_event(&e, symbol!("cmp_unrth"), user, amount.clone());
+        Ok(amount)
+    }
+
+    fn rebase(e: Env) -> u32 {
+        let epochs = settle_epochs(&e);
+        let bounty = get_bounty(&e);
+        if epochs > 0 && bounty > BigInt::zero(&e) {
+            let user_id = Identifier::from(e.invoker());
+            mint_token(&e, user_id.clone(), bounty.clone());
+            user_event(&e, symbol!("bounty"), user_id, bounty);
         }
-        if voyage_info.max_vygs.clone() - voyage_info.n_embarked.clone() < num_voyages.clone() {
-            panic!("not enough voyage available");
+        return epochs;
+    }
+
+    /// Requires approval for `transfer_from` before running
+    fn voyage(
+        e: Env,
+        voyage_id: i32,
+        num_voyages: BigInt,
+        max_price: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        check_unpaused(&e)?;
+        let mut voyage_info = get_voyage(&e, voyage_id)?;
+        if is_bond_market(&e, voyage_id) {
+            return Err(SevenSeasError::IsBondMarket);
         }
-        let transfer_amount = voyage_info.price.clone() * num_voyages.clone();
         let user_id = Identifier::from(e.invoker());
+        let cost = fund_voyage(
+            &e,
+            voyage_id,
+            user_id.clone(),
+            &mut voyage_info,
+            num_voyages.clone(),
+            max_price,
+        )?;
 
-        let voyage_asset_client = crate::token::Client::new(&e, voyage_info.vyg_asset.clone());
+        let current = get_user_voyage(&e, user_id.clone(), voyage_id);
+        set_user_voyage(
+            &e,
+            user_id.clone(),
+            voyage_id,
+            current + num_voyages.clone(),
+        );
 
-        voyage_asset_client.xfer_from(
-            &Signature::Invoker,
-            &BigInt::zero(&e),
-            &user_id,
-            &get_contract_id(&e),
-            &transfer_amount,
+        set_voyage(&e, voyage_id, voyage_info);
+        voyage_event(
+            &e,
+            symbol!("voyage"),
+            user_id,
+            voyage_id,
+            (num_voyages, cost),
         );
+        Ok(())
+    }
 
-        set_user_voyage(&e, user_id, voyage_id, num_voyages.clone());
+    fn bond(
+        e: Env,
+        voyage_id: i32,
+        num_voyages: BigInt,
+        max_price: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        check_unpaused(&e)?;
+        let mut voyage_info = get_voyage(&e, voyage_id)?;
+        if !is_bond_market(&e, voyage_id) {
+            return Err(SevenSeasError::NotBondMarket);
+        }
+        let user_id = Identifier::from(e.invoker());
+        let cost = fund_voyage(
+            &e,
+            voyage_id,
+            user_id.clone(),
+            &mut voyage_info,
+            num_voyages.clone(),
+            max_price,
+        )?;
 
-        //update voyage info
-        voyage_info.n_embarked = voyage_info.n_embarked.clone() + num_voyages.clone();
+        // bonds settle straight into a vesting position, so they are closed on the offering right away
+        let closed = get_voyage_closed(&e, voyage_id);
+        set_voyage_closed(&e, voyage_id, closed + num_voyages.clone());
+        let payout = num_voyages.clone() * voyage_info.reward.clone();
+        let claimed = add_bond(&e, user_id.clone(), voyage_id, payout.clone());
 
         set_voyage(&e, voyage_id, voyage_info);
+        voyage_event(
+            &e,
+            symbol!("bond"),
+            user_id,
+            voyage_id,
+            (num_voyages, cost, payout, claimed),
+        );
+        Ok(())
     }
 
-    fn end_voyage(e: Env, voyage_id: i32) {
+    fn claim_vest(e: Env, voyage_id: i32) -> Result<BigInt, SevenSeasError> {
         let user_id = Identifier::from(e.invoker());
-        let user_voyage_amt = get_user_voyage(&e, user_id.clone(), voyage_id);
-        // NOTE: this may not be necessary, could just let contract panic from null result, but think this is clearer
-        if user_voyage_amt == BigInt::zero(&e) {
-            panic!("user has no voyages for this voyage id");
+        if !has_user_bond(&e, user_id.clone(), voyage_id) {
+            return Err(SevenSeasError::NoBond);
         }
-        // user gets shells equal to the number of voyages they finished
-        let mint_amount = user_voyage_amt.clone() * BigInt::from_i64(&e, SCALER);
-        mint_token(&e, user_id.clone(), mint_amount);
-        remove_user_voyage(&e, user_id, voyage_id);
+        let claimed = claim_bond(&e, user_id.clone(), voyage_id);
+        voyage_event(
+            &e,
+            symbol!("claim_vest"),
+            user_id,
+            voyage_id,
+            claimed.clone(),
+        );
+        return Ok(claimed);
     }
 
-    fn raid(e: Env, voyage_id: i32, voyager_id: Identifier) {
-        // waiting on PRNG pull request https://github.com/stellar/rs-soroban-env/pull/544
-        panic!("Not Implemented");
+    fn end_voyage(e: Env, voyage_id: i32) -> Result<(), SevenSeasError> {
+        let user_id = Identifier::from(e.invoker());
+        let (user_voyage_amt, mint_amount) = quote_end_voyage(&e, user_id.clone(), voyage_id)?;
+        mint_token(&e, user_id.clone(), mint_amount.clone());
+        close_user_voyage(&e, user_id.clone(), voyage_id, user_voyage_amt.clone());
+        voyage_event(
+            &e,
+            symbol!("end_voyage"),
+            user_id,
+            voyage_id,
+            (user_voyage_amt, mint_amount),
+        );
+        Ok(())
+    }
 
-        let user_voyage_amt = get_user_voyage(&e, voyager_id.clone(), voyage_id);
+    fn commit_rd(
+        e: Env,
+        voyage_id: i32,
+        voyager_id: Identifier,
+        hash: BytesN<32>,
+    ) -> Result<u32, SevenSeasError> {
+        check_unpaused(&e)?;
+        // the host has no randomness the raider can't predict, so the secret is mixed with the oracle's
+        if !has_oracle(&e) {
+            return Err(SevenSeasError::NoOracle);
+        }
         let raider_id = Identifier::from(e.invoker());
-        // NOTE: this may not be necessary, could just let contract panic from null result, but think this is clearer
-        if user_voyage_amt == BigInt::zero(&e) {
-            panic!("user has no voyages for this voyage id");
-        }
-        // calculate the amount of shells required to perform the raid
-        let raid_cost = user_voyage_amt / BigInt::from_i64(&e, 100) * BigInt::from_i64(&e, SCALER);
-        // burn the shells
-        burn_token(&e, raider_id, raid_cost);
-
-        // calculate probability of raid - scaled by how long it has been since the last raid - we target 1 raid per 600 blocks - probability cant be greater than 1.25%
-        // scale by 10000 to avoid floating point math
-        let current_block: u32 = e.ledger().sequence().into();
-        let mut raid_probability: u32 =
-            (current_block - get_last_raid_block(&e)) * 10000 / get_target_raid_interval(&e);
-        if raid_probability > 12500 {
-            raid_probability = 12500;
-        }
-        // calculate the max random number for a successful raid based on raid probability
-        let max_ok_PRNG = MAX / 1000000 * raid_probability;
-        // check if the raid was successful
-        // TODO: Use PRNG to determine if raid was successful, waiting on this PR https://github.com/stellar/rs-soroban-env/pull/544
-        // let prng_u32: u32 = e.prng_next_u32(RawVal::from_bool(true));
-        // if prng_u32 < max_ok_PRNG {
-        //     // raid was successful, user loses all their voyages, raider gets shells
-        //     remove_user_voyage(&e, voyager_id, voyage_id);
-        //     let mint_amount = user_voyage_amt * BigInt::from_i64(&e, SCALER);
-        //     mint_token(&e, raider_id.clone(), mint_amount);
-        //     e.events().publish(
-        //         (symbol!("raid_won"), voyage_id, voyager_id, current_block),
-        //         true,
-        //     );
-        // } else {
-        //     e.events().publish(
-        //         (symbol!("raid_won"), voyage_id, voyager_id, current_block),
-        //         false,
-        //     );
-        // }
-        // update last raid block
-        set_last_raid(&e);
+        // the odds are locked in when the raid is paid for so the raider can't improve them by delaying the reveal
+        let quote = pay_raid(&e, raider_id.clone(), voyage_id, voyager_id.clone())?;
+        let request_id = get_last_request_id(&e) + 1;
+        let commitment = RaidCommit {
+            raider: raider_id.clone(),
+            voyage_id,
+            voyager: voyager_id.clone(),
+            n_vygs: quote.n_vygs,
+            cost: quote.cost,
+            hash,
+            odds: quote.odds,
+            ledger: e.ledger().sequence(),
+            beacon: BytesN::from_array(&e, &[0; 32]),
+            drawn: 0,
+        };
+        set_raid_commit(&e, request_id, commitment);
+        set_last_request_id(&e, request_id);
+        voyage_event(
+            &e,
+            symbol!("raid_cmt"),
+            raider_id,
+            voyage_id,
+            (voyager_id, request_id),
+        );
+        OracleClient::new(&e, get_oracle(&e)).request(&request_id);
+        Ok(request_id)
+    }
+
+    fn reveal_rd(e: Env, request_id: u32, secret: BytesN<32>) -> Result<(), SevenSeasError> {
+        if !has_raid_commit(&e, request_id) {
+            return Err(SevenSeasError::NoRaid);
+        }
+        let commitment = get_raid_commit(&e, request_id);
+        if commitment.drawn == 0 {
+            return Err(SevenSeasError::RevealTooEarly);
+        }
+        if e.ledger().sequence() > commitment.drawn + REVEAL_WINDOW {
+            return Err(SevenSeasError::RaidExpired);
+        }
+        let secret_bytes: Bytes = secret.into();
+        if e.compute_hash_sha256(&secret_bytes) != commitment.hash {
+            return Err(SevenSeasError::SecretMismatch);
+        }
+        remove_raid_commit(&e, request_id);
+
+        // the raider committed to the secret before the oracle's randomness existed, and the oracle
+        // only ever saw its hash, so neither could steer the roll
+        let mut seed: Bytes = commitment.beacon.into();
+        seed.append(&secret_bytes);
+        let mut randomness = OraclePrng::new(&e, seed);
+        settle_raid(
+            &e,
+            commitment.raider,
+            commitment.voyage_id,
+            commitment.voyager,
+            commitment.n_vygs,
+            commitment.odds,
+            &mut randomness,
+        )
+    }
+
+    fn expire_rd(e: Env, request_id: u32) -> Result<(), SevenSeasError> {
+        let current_block = e.ledger().sequence();
+        if has_raid_request(&e, request_id) {
+            let request = get_raid_request(&e, request_id);
+            if current_block <= request.ledger + REQUEST_WINDOW {
+                return Err(SevenSeasError::RaidNotExpired);
+            }
+            remove_raid_request(&e, request_id);
+            refund_raid(
+                &e,
+                request.raider,
+                request.voyage_id,
+                request.voyager,
+                request_id,
+                request.cost,
+            );
+            return Ok(());
+        }
+        if !has_raid_commit(&e, request_id) {
+            return Err(SevenSeasError::NoRaid);
+        }
+        let commitment = get_raid_commit(&e, request_id);
+        // the oracle never answered, so the raider didn't get their roll and is refunded
+        if commitment.drawn == 0 {
+            if current_block <= commitment.ledger + REQUEST_WINDOW {
+                return Err(SevenSeasError::RaidNotExpired);
+            }
+            remove_raid_commit(&e, request_id);
+            refund_raid(
+                &e,
+                commitment.raider,
+                commitment.voyage_id,
+                commitment.voyager,
+                request_id,
+                commitment.cost,
+            );
+            return Ok(());
+        }
+        if current_block <= commitment.drawn + REVEAL_WINDOW {
+            return Err(SevenSeasError::RaidNotExpired);
+        }
+        remove_raid_commit(&e, request_id);
+        voyage_event(
+            &e,
+            symbol!("raid_exp"),
+            commitment.raider,
+            commitment.voyage_id,
+            commitment.voyager,
+        );
+        Ok(())
+    }
+
+    fn request_rd(e: Env, voyage_id: i32, voyager_id: Identifier) -> Result<u32, SevenSeasError> {
+        check_unpaused(&e)?;
+        if !has_oracle(&e) {
+            return Err(SevenSeasError::NoOracle);
+        }
+        let raider_id = Identifier::from(e.invoker());
+        let quote = pay_raid(&e, raider_id.clone(), voyage_id, voyager_id.clone())?;
+        let request_id = get_last_request_id(&e) + 1;
+        let request = RaidRequest {
+            raider: raider_id.clone(),
+            voyage_id,
+            voyager: voyager_id.clone(),
+            n_vygs: quote.n_vygs,
+            cost: quote.cost,
+            odds: quote.odds,
+            ledger: e.ledger().sequence(),
+        };
+        set_raid_request(&e, request_id, request);
+        set_last_request_id(&e, request_id);
+        voyage_event(
+            &e,
+            symbol!("raid_req"),
+            raider_id,
+            voyage_id,
+            (voyager_id, request_id),
+        );
+        OracleClient::new(&e, get_oracle(&e)).request(&request_id);
+        return Ok(request_id);
+    }
+
+    fn fulfill_rd(e: Env, request_id: u32, randomness: BytesN<32>) -> Result<(), SevenSeasError> {
+        if !has_oracle(&e) {
+            return Err(SevenSeasError::NoOracle);
+        }
+        if Identifier::from(e.invoker()) != Identifier::Contract(get_oracle(&e)) {
+            return Err(SevenSeasError::NotOracle);
+        }
+        if has_raid_commit(&e, request_id) {
+            let mut commitment = get_raid_commit(&e, request_id);
+            // the randomness can only be drawn once, or the oracle could roll again
+            if commitment.drawn != 0 {
+                return Err(SevenSeasError::NoRaid);
+            }
+            commitment.beacon = randomness;
+            commitment.drawn = e.ledger().sequence();
+            set_raid_commit(&e, request_id, commitment.clone());
+            voyage_event(
+                &e,
+                symbol!("raid_draw"),
+                commitment.raider,
+                commitment.voyage_id,
+                (commitment.voyager, request_id),
+            );
+            return Ok(());
+        }
+        if !has_raid_request(&e, request_id) {
+            return Err(SevenSeasError::NoRaid);
+        }
+        let request = get_raid_request(&e, request_id);
+        remove_raid_request(&e, request_id);
+        let mut randomness = OraclePrng::new(&e, randomness.into());
+        settle_raid(
+            &e,
+            request.raider,
+            request.voyage_id,
+            request.voyager,
+            request.n_vygs,
+            request.odds,
+            &mut randomness,
+        )
+    }
+
+    fn acpt_capn(e: Env) -> Result<(), SevenSeasError> {
+        let proposal = read_proposal(&e)?;
+        if Identifier::from(e.invoker()) != proposal.nominee {
+            return Err(SevenSeasError::NotNominee);
+        }
+        if e.ledger().sequence() > proposal.expires {
+            return Err(SevenSeasError::ProposalExpired);
+        }
+        remove_proposal(&e);
+        remove_council(&e);
+        // the outgoing captain's roles go with the captaincy
+        for role in ROLES {
+            if has_role(&e, role.clone(), read_captain(&e)) {
+                revoke(&e, role.clone(), read_captain(&e));
+                grant(&e, role, proposal.nominee.clone());
+            }
+        }
+        captain_event(&e, symbol!("acpt_capn"), proposal.nominee.clone());
+        write_captain(&e, proposal.nominee);
+        Ok(())
     }
 
     /******** Read functions *********/
-    fn decimals(e: Env) -> BigInt {
+    fn allowance(e: Env, from: Identifier, spender: Identifier) -> BigInt {
+        return get_allowance(&e, from, spender);
+    }
+
+    fn approve(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        spender: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        let from_id = from.identifier(&e);
+        verify_and_consume_nonce(&e, &from, &nonce)?;
+        verify(
+            &e,
+            &from,
+            symbol!("approve"),
+            (&from_id, nonce, &spender, &amount),
+        );
+        set_allowance(&e, from_id.clone(), spender.clone(), amount.clone());
+        user_event(&e, symbol!("approve"), from_id, (spender, amount));
+        Ok(())
+    }
+
+    fn balance(e: Env, id: Identifier) -> BigInt {
+        return to_doubloons(&e, get_user_buried(&e, id));
+    }
+
+    fn xfer(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        to: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        let from_id = from.identifier(&e);
+        verify_and_consume_nonce(&e, &from, &nonce)?;
+        verify(&e, &from, symbol!("xfer"), (&from_id, nonce, &to, &amount));
+        xfer_buried(&e, from_id, to, amount)
+    }
+
+    fn xfer_from(
+        e: Env,
+        spender: Signature,
+        nonce: BigInt,
+        from: Identifier,
+        to: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        let spender_id = spender.identifier(&e);
+        verify_and_consume_nonce(&e, &spender, &nonce)?;
+        verify(
+            &e,
+            &spender,
+            symbol!("xfer_from"),
+            (&spender_id, nonce, &from, &to, &amount),
+        );
+        spend_allowance(&e, from.clone(), spender_id, amount.clone())?;
+        xfer_buried(&e, from, to, amount)
+    }
+
+    fn decimals(e: Env) -> u32 {
         return get_decimals(&e);
     }
 
+    fn name(e: Env) -> Bytes {
+        return Bytes::from_slice(&e, b"buried doubloon");
+    }
+
+    fn symbol(e: Env) -> Bytes {
+        return Bytes::from_slice(&e, b"sDOUBLOON");
+    }
+
+    fn nonce(e: Env, id: Identifier) -> BigInt {
+        return get_nonce(&e, id);
+    }
+
+    fn wrap(e: Env, amount: BigInt) -> Result<BigInt, SevenSeasError> {
+        let user_id = Identifier::from(e.invoker());
+        let shares = wrap_buried(&e, user_id.clone(), amount.clone())?;
+        user_event(&e, symbol!("wrap"), user_id, (amount, shares.clone()));
+        return Ok(shares);
+    }
+
+    fn unwrap(e: Env, amount: BigInt) -> Result<BigInt, SevenSeasError> {
+        let user_id = Identifier::from(e.invoker());
+        let unwrapped = unwrap_buried(&e, user_id.clone(), amount.clone())?;
+        user_event(&e, symbol!("unwrap"), user_id, (amount, unwrapped.clone()));
+        return Ok(unwrapped);
+    }
+
+    fn get_index(e: Env) -> BigInt {
+        return get_new_index(&e);
+    }
+
+    fn excess_rsv(e: Env) -> BigInt {
+        return excess_reserves(&e, get_new_index(&e));
+    }
+
+    fn runway(e: Env) -> u32 {
+        return runway(&e, get_new_index(&e));
+    }
+
+    fn epoch(e: Env) -> EpochInfo {
+        return epoch_info(&e);
+    }
+
+    fn prot_state(e: Env) -> ProtocolState {
+        let index = get_new_index(&e);
+        ProtocolState {
+            base_token: get_base_token(&e),
+            captain: read_captain(&e),
+            decimals: get_decimals(&e),
+            rate: get_rate(&e),
+            index: index.clone(),
+            last_ldgr: get_last_block(&e),
+            epoch: epoch_info(&e),
+            bounty: get_bounty(&e),
+            ttl_buried: get_total_buried(&e),
+            ttl_queued: get_total_queued(&e),
+            supply: get_supply(&e),
+            excess_rsv: excess_reserves(&e, index),
+            warmup: get_warmup(&e),
+            cooldown: get_cooldown(&e),
+            exit_pen: get_exit_penalty(&e),
+            tgt_raid: get_target_raid_interval(&e),
+            last_raid: get_last_raid_block(&e),
+            last_vyg: get_last_voyage_id(&e),
+            paused: get_paused(&e),
+        }
+    }
+
+    fn user_state(e: Env, user_id: Identifier) -> UserState {
+        let index = get_new_index(&e);
+        let buried = get_user_buried(&e, user_id.clone());
+        let mut locked = BigInt::zero(&e);
+        for position in get_user_locks(&e, user_id.clone()).iter() {
+            locked = locked + lock_value(&e, &position.unwrap(), index.clone());
+        }
+        let mut voyages = Map::new(&e);
+        for voyage_id in get_user_voyage_ids(&e, user_id.clone()).iter() {
+            let voyage_id = voyage_id.unwrap();
+            // positions are only opened on offerings that exist
+            if let Ok(status) = voyage_status(&e, user_id.clone(), voyage_id) {
+                voyages.set(voyage_id, status);
+            }
+        }
+        UserState {
+            doubloons: get_base_token_client(&e).balance(&user_id),
+            buried: buried.clone(),
+            value: doubloons_at(&e, buried, index),
+            locked,
+            queued: queued_amount(&e, get_user_warmup(&e, user_id.clone()))
+                + queued_amount(&e, get_user_cooldown(&e, user_id)),
+            voyages,
+        }
+    }
+
+    fn wrap_prvw(e: Env, amount: BigInt) -> BigInt {
+        return to_shares(&e, amount);
+    }
+
+    fn unwrp_prvw(e: Env, amount: BigInt) -> BigInt {
+        return to_doubloons(&e, amount);
+    }
+
     fn get_buried(e: Env, user_id: Identifier) -> BigInt {
         return get_user_buried(&e, user_id);
     }
 
-    fn get_voyage(e: Env, voyage_id: i32) -> VoyageInfo {
+    fn get_locks(e: Env, user_id: Identifier) -> Vec<LockPosition> {
+        get_user_locks(&e, user_id)
+    }
+
+    fn boost_of(e: Env, user_id: Identifier) -> BigInt {
+        user_boost(&e, user_id)
+    }
+
+    fn get_wrmp(e: Env, user_id: Identifier) -> Vec<QueueEntry> {
+        get_user_warmup(&e, user_id)
+    }
+
+    fn get_cool(e: Env, user_id: Identifier) -> Vec<QueueEntry> {
+        get_user_cooldown(&e, user_id)
+    }
+
+    fn get_voyage(e: Env, voyage_id: i32) -> Result<VoyageInfo, SevenSeasError> {
         return get_voyage(&e, voyage_id);
     }
 
@@ -342,58 +2052,476 @@ impl SevenSeasTrait for SevenSeas {
         return get_user_voyage(&e, user_id, voyage_id);
     }
 
+    fn vyg_status(
+        e: Env,
+        user_id: Identifier,
+        voyage_id: i32,
+    ) -> Result<VoyageStatus, SevenSeasError> {
+        return voyage_status(&e, user_id, voyage_id);
+    }
+
+    fn bond_info(e: Env, user_id: Identifier, voyage_id: i32) -> BondInfo {
+        let position = get_user_bond(&e, user_id, voyage_id);
+        return BondInfo {
+            claimable: vested_amount(&e, &position),
+            payout: position.payout,
+            vesting: position.vesting,
+            last_ldgr: position.last_ldgr,
+        };
+    }
+
+    fn cur_price(e: Env, voyage_id: i32) -> Result<BigInt, SevenSeasError> {
+        let voyage_info = get_voyage(&e, voyage_id)?;
+        Ok(get_voyage_price(&e, voyage_id, &voyage_info))
+    }
+
+    fn recon_vyg(e: Env, voyage_id: i32) -> Result<bool, SevenSeasError> {
+        return voyage_reconciles(&e, voyage_id);
+    }
+
     fn get_l_raid(e: Env) -> u32 {
         return get_last_raid_block(&e);
     }
 
+    fn get_rd_cmt(e: Env, request_id: u32) -> Result<RaidCommit, SevenSeasError> {
+        if !has_raid_commit(&e, request_id) {
+            return Err(SevenSeasError::NoRaid);
+        }
+        Ok(get_raid_commit(&e, request_id))
+    }
+
+    fn prv_bury(e: Env, amount: BigInt) -> BigInt {
+        to_shares(&e, amount)
+    }
+
+    fn prv_unrth(e: Env, amount: BigInt) -> BigInt {
+        to_doubloons(&e, amount)
+    }
+
+    fn prv_vyg(e: Env, voyage_id: i32, num_voyages: BigInt) -> Result<BigInt, SevenSeasError> {
+        let voyage_info = get_voyage(&e, voyage_id)?;
+        Ok(quote_voyage(&e, voyage_id, &voyage_info, num_voyages.clone())? * num_voyages)
+    }
+
+    fn prv_end_v(e: Env, user_id: Identifier, voyage_id: i32) -> Result<BigInt, SevenSeasError> {
+        let (_, mint_amount) = quote_end_voyage(&e, user_id, voyage_id)?;
+        Ok(mint_amount)
+    }
+
+    fn raid_odds(
+        e: Env,
+        voyage_id: i32,
+        voyager_id: Identifier,
+    ) -> Result<RaidQuote, SevenSeasError> {
+        quote_raid(&e, voyage_id, voyager_id)
+    }
+
+    fn get_rd_req(e: Env, request_id: u32) -> Result<RaidRequest, SevenSeasError> {
+        if !has_raid_request(&e, request_id) {
+            return Err(SevenSeasError::NoRaid);
+        }
+        Ok(get_raid_request(&e, request_id))
+    }
+
+    fn capn_prop(e: Env) -> Result<CapnProposal, SevenSeasError> {
+        read_proposal(&e)
+    }
+
+    fn has_role(e: Env, role: Role, id: Identifier) -> bool {
+        has_role(&e, role, id)
+    }
+
+    fn council(e: Env) -> Result<Council, SevenSeasError> {
+        read_council(&e)
+    }
+
+    fn get_action(e: Env, action_id: u32) -> Result<ActionProp, SevenSeasError> {
+        read_action(&e, action_id)
+    }
+
     /******** Admin functions ********/
-    fn new_voyage(e: Env, vyg_asset: BytesN<32>, price: BigInt, max_vygs: BigInt) {
-        check_captain(&e, &Signature::Invoker);
-        let voyage_id = get_last_voyage_id(&e) + 1;
-        let voyage_info = VoyageInfo {
-            vyg_asset,
-            price,
-            max_vygs,
-            n_embarked: BigInt::zero(&e),
-            expiration: e.ledger().sequence() + WEEK_IN_BLOCKS,
-        };
-        set_voyage(&e, voyage_id, voyage_info);
-        set_last_voyage_id(&e, voyage_id);
+    fn new_voyage(
+        e: Env,
+        vyg_asset: BytesN<32>,
+        price: BigInt,
+        max_vygs: BigInt,
+        reward: BigInt,
+        start_ldgr: u32,
+        embark_dl: u32,
+        sail_dur: u32,
+        price_mode: PriceMode,
+    ) -> Result<(), SevenSeasError> {
+        //check that invoker is the voyage master
+        check_role(&e, Role::VygMaster, &Signature::Invoker)?;
+        // voyages embarked on at the deadline would otherwise complete in the next block
+        if sail_dur == 0 {
+            return Err(SevenSeasError::ZeroLength);
+        }
+        let voyage_id = create_voyage(
+            &e, vyg_asset, price, max_vygs, reward, start_ldgr, embark_dl, sail_dur, price_mode,
+        )?;
+        voyage_event(
+            &e,
+            symbol!("new_voyage"),
+            Identifier::from(e.invoker()),
+            voyage_id,
+            get_voyage(&e, voyage_id)?,
+        );
+        Ok(())
+    }
+
+    fn new_bond(
+        e: Env,
+        vyg_asset: BytesN<32>,
+        price: BigInt,
+        max_vygs: BigInt,
+        reward: BigInt,
+        start_ldgr: u32,
+        embark_dl: u32,
+        vest_len: u32,
+        price_mode: PriceMode,
+    ) -> Result<(), SevenSeasError> {
+        //check that invoker is the voyage master
+        check_role(&e, Role::VygMaster, &Signature::Invoker)?;
+        if vest_len == 0 {
+            return Err(SevenSeasError::ZeroLength);
+        }
+        let voyage_id = create_voyage(
+            &e, vyg_asset, price, max_vygs, reward, start_ldgr, embark_dl, 0, price_mode,
+        )?;
+        set_bond_vesting(&e, voyage_id, vest_len);
+        voyage_event(
+            &e,
+            symbol!("new_bond"),
+            Identifier::from(e.invoker()),
+            voyage_id,
+            (get_voyage(&e, voyage_id)?, vest_len),
+        );
+        Ok(())
     }
 
     // transfers contract holdings
-    fn xfer_held(e: Env, token_id: BytesN<32>, to: Identifier, amount: BigInt) {
+    fn xfer_held(
+        e: Env,
+        token_id: BytesN<32>,
+        to: Identifier,
+        amount: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        //check that invoker is the treasurer
+        check_role(&e, Role::Treasurer, &Signature::Invoker)?;
+        remove_reserve(&e, token_id.clone(), amount.clone());
+        transfer(&e, token_id.clone(), to.clone(), amount.clone());
+        role_event(&e, symbol!("xfer_held"), (token_id, to, amount));
+        Ok(())
+    }
+
+    fn set_rate(e: Env, rate: BigInt) -> Result<(), SevenSeasError> {
+        //check that invoker is the rate keeper
+        check_role(&e, Role::RateKeeper, &Signature::Invoker)?;
+        if rate < BigInt::zero(&e) {
+            return Err(SevenSeasError::NegativeAmount);
+        }
+        settle_pending(&e)?;
+        set_rate(&e, rate.clone());
+        role_event(&e, symbol!("set_rate"), rate);
+        Ok(())
+    }
+
+    fn set_epoch(e: Env, epoch_len: u32) -> Result<(), SevenSeasError> {
         //check that invoker is admin
-        check_captain(&e, &Signature::Invoker);
-        transfer(&e, token_id, to, amount);
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetEpoch(epoch_len))
     }
 
-    fn set_rate(e: Env, rate: BigInt) {
+    fn set_bounty(e: Env, bounty: BigInt) -> Result<(), SevenSeasError> {
         //check that invoker is admin
-        check_captain(&e, &Signature::Invoker);
-        let new_index = get_new_index(&e);
-        set_index(&e, new_index);
-        set_last_block(&e);
-        set_rate(&e, rate);
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetBounty(bounty))
+    }
+
+    fn set_rfv(e: Env, asset: BytesN<32>, value: BigInt) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetRfv(RfvArgs { asset, value }))
+    }
+
+    fn set_rwdcap(e: Env, cap: BigInt) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetRwdCap(cap))
     }
 
-    fn set_tgt_ri(e: Env, interval: u32) {
+    fn set_warmup(e: Env, blocks: u32) -> Result<(), SevenSeasError> {
         //check that invoker is admin
-        check_captain(&e, &Signature::Invoker);
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetWarmup(blocks))
+    }
+
+    fn set_cooldn(e: Env, blocks: u32) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetCooldn(blocks))
+    }
+
+    fn set_tgt_ri(e: Env, interval: u32) -> Result<(), SevenSeasError> {
+        //check that invoker is the rate keeper
+        check_role(&e, Role::RateKeeper, &Signature::Invoker)?;
         set_target_raid_interval(&e, interval);
+        role_event(&e, symbol!("set_tgt_ri"), interval);
+        Ok(())
+    }
+
+    fn fix_vyg(
+        e: Env,
+        voyage_id: i32,
+        positions: Map<Identifier, BigInt>,
+        closed: BigInt,
+    ) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(
+            &e,
+            AdminAction::FixVyg(FixArgs {
+                voyage_id,
+                positions,
+                closed,
+            }),
+        )
+    }
+
+    fn set_exit_p(e: Env, penalty: BigInt) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetExitP(penalty))
+    }
+
+    fn set_ctrl(
+        e: Env,
+        voyage_id: i32,
+        bcv: BigInt,
+        floor: BigInt,
+        decay_len: u32,
+    ) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(
+            &e,
+            AdminAction::SetCtrl(CtrlArgs {
+                voyage_id,
+                bcv,
+                floor,
+                decay_len,
+            }),
+        )
+    }
+
+    fn set_oracle(e: Env, oracle_id: BytesN<32>) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetOracle(oracle_id))
+    }
+
+    fn set_wrap(e: Env, token_id: BytesN<32>) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetWrap(token_id))
+    }
+
+    fn prop_capn(e: Env, nominee: Identifier) -> Result<u32, SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::PropCapn(nominee))?;
+        Ok(read_proposal(&e)?.expires)
+    }
+
+    fn cncl_capn(e: Env) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::CnclCapn)
     }
 
-    fn set_capn(e: Env, new_admin: Identifier) {
+    fn rnce_capn(e: Env) -> Result<(), SevenSeasError> {
         //check that invoker is admin
-        check_captain(&e, &Signature::Invoker);
-        write_captain(&e, new_admin);
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::RnceCapn)
     }
+
+    fn grant_role(e: Env, role: Role, id: Identifier) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::GrantRole(RoleKey { role, id }))
+    }
+
+    fn rvke_role(e: Env, role: Role, id: Identifier) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::RvkeRole(RoleKey { role, id }))
+    }
+
+    fn set_cncl(e: Env, members: Vec<Identifier>, threshold: u32) -> Result<(), SevenSeasError> {
+        //check that invoker is admin
+        check_captain(&e, &Signature::Invoker)?;
+        run_action(&e, AdminAction::SetCouncil(Council { members, threshold }))
+    }
+
+    /******** Guardian functions ********/
+    fn pause(e: Env) -> Result<(), SevenSeasError> {
+        //check that invoker is the guardian
+        check_role(&e, Role::Guardian, &Signature::Invoker)?;
+        set_paused(&e, true);
+        role_event(&e, symbol!("pause"), ());
+        Ok(())
+    }
+
+    fn unpause(e: Env) -> Result<(), SevenSeasError> {
+        //check that invoker is the guardian
+        check_role(&e, Role::Guardian, &Signature::Invoker)?;
+        set_paused(&e, false);
+        role_event(&e, symbol!("unpause"), ());
+        Ok(())
+    }
+
+    /******** Council functions ********/
+    fn prop_act(e: Env, action: AdminAction) -> Result<u32, SevenSeasError> {
+        let member = Identifier::from(e.invoker());
+        check_member(&e, &Signature::Invoker)?;
+        let (action_id, expires) = write_action(&e, member.clone(), action.clone());
+        user_event(
+            &e,
+            symbol!("prop_act"),
+            member,
+            (action_id, action, expires),
+        );
+        Ok(action_id)
+    }
+
+    fn aprv_act(e: Env, action_id: u32) -> Result<u32, SevenSeasError> {
+        let member = Identifier::from(e.invoker());
+        let council = check_member(&e, &Signature::Invoker)?;
+        let mut proposal = read_action(&e, action_id)?;
+        if e.ledger().sequence() > proposal.expires {
+            return Err(SevenSeasError::ProposalExpired);
+        }
+        if proposal.approvals.contains(&member) {
+            return Err(SevenSeasError::AlreadyApproved);
+        }
+        proposal.approvals.push_back(member.clone());
+        set_action(&e, action_id, proposal.clone());
+        user_event(&e, symbol!("aprv_act"), member, action_id);
+        Ok(count_approvals(&council, &proposal))
+    }
+
+    fn exec_act(e: Env, action_id: u32) -> Result<(), SevenSeasError> {
+        let member = Identifier::from(e.invoker());
+        let council = check_member(&e, &Signature::Invoker)?;
+        let proposal = read_action(&e, action_id)?;
+        if e.ledger().sequence() > proposal.expires {
+            return Err(SevenSeasError::ProposalExpired);
+        }
+        if count_approvals(&council, &proposal) < council.threshold {
+            return Err(SevenSeasError::BelowThreshold);
+        }
+        remove_action(&e, action_id);
+        user_event(&e, symbol!("exec_act"), member, action_id);
+        run_action(&e, proposal.action)
+    }
+}
+
+/****** Errors *******/
+// error codes are part of the contract's interface, new errors get new codes and existing codes never change
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SevenSeasError {
+    NotCaptain = 1,             //invoker is not the captain
+    AlreadyInitialized = 2,     //the sea has already been initialized
+    NotOracle = 3,              //invoker is not the randomness oracle
+    InvalidNonce = 4,           //signature nonce doesn't match the user's next nonce
+    NoProposal = 5,             //no captain proposal is pending
+    NotNominee = 6,             //invoker is not the proposed captain
+    ProposalExpired = 7,        //captain proposal can no longer be accepted
+    MissingRole = 8,            //invoker doesn't hold the role the function needs
+    Paused = 9,                 //the sea is paused by the guardian
+    NegativeAmount = 10,        //amount can't be negative
+    OutOfRange = 11,            //fraction must be between 0 and 100%
+    ZeroLength = 12,            //length in blocks must be positive
+    InvalidReward = 13,         //voyage reward must be positive
+    InvalidReserve = 14,        //reserve price is above the voyage price
+    InvalidSchedule = 15,       //voyage blocks are out of order
+    InvalidLock = 16,           //lock duration isn't a lock tier
+    EpochsPending = 18,         //more than MAX_EPOCHS epochs are pending, call rebase first
+    VoyageNotStarted = 20,      //voyage can't be embarked on yet
+    VoyageClosed = 21,          //voyage embark deadline has passed
+    NotEnoughVoyages = 22,      //not enough voyages left to embark on
+    PriceAboveMax = 23,         //voyage price is above the max price
+    VoyageNotCompleted = 24,    //voyage is still at sea
+    VoyageCompleted = 25,       //voyage has completed
+    IsBondMarket = 26,          //voyage is a bond market
+    NotBondMarket = 27,         //voyage is not a bond market
+    NoVoyages = 28,             //user has no voyages on the voyage
+    NoBond = 29,                //user has no vesting bond on the voyage
+    DoesNotReconcile = 31,      //fixed voyage accounting doesn't reconcile
+    AlreadyTracked = 32,        //voyage already tracks its positions
+    NoVoyage = 33,              //no voyage offering with the id
+    Untracked = 34,             //voyage positions need repairing with fix_vyg
+    NotEnoughBuried = 40,       //not enough buried doubloons
+    InsufficientAllowance = 41, //spender allowance is too low
+    DoubloonsLocked = 42,       //buried doubloons are locked
+    LockNotExpired = 43,        //lock hasn't expired yet
+    NoLock = 44,                //user has no lock at the index
+    CooldownActive = 45,        //unearthing must be requested while a cooldown is set
+    NothingToClaim = 46,        //nothing is ready to be claimed
+    NoWrappedToken = 47,        //wrapped token hasn't been set
+    LockTooShort = 48,          //lock would expire before the warmup
+    NoOracle = 61,              //oracle hasn't been set
+    NoRaid = 63,                //no outstanding raid
+    SecretMismatch = 64,        //secret doesn't match the raid commitment
+    RevealTooEarly = 65,        //oracle randomness for the raid hasn't arrived
+    RaidExpired = 66,           //raid reveal window has passed
+    RaidNotExpired = 67,        //raid can still be revealed or answered by the oracle
+    NotCouncil = 80,            //the council isn't the captain
+    NotMember = 81,             //invoker isn't on the council
+    InvalidCouncil = 82,        //threshold must be between 1 and the number of distinct members
+    NoAction = 83,              //no admin action is proposed with the id
+    AlreadyApproved = 84,       //member already approved the action
+    BelowThreshold = 85,        //not enough members approved the action
 }
 
 /****** Objects *******/
 #[derive(Clone)]
 #[contracttype]
 pub struct VoyageInfo {
+    pub vyg_asset: BytesN<32>, //asset being used to fund the voyage
+    pub price: BigInt,         //the cost to embark on a voyage in voyage asset
+    pub max_vygs: BigInt,      //max number of voyages that can be entered for doubloons
+    pub n_embarked: BigInt,    //number of voyages that have been embarked on
+    pub reward: BigInt,        //doubloons paid out per completed voyage
+    pub expiration: u32,       //last block the voyage is at sea (embark_dl + sail_dur)
+    pub start_ldgr: u32,       //first block the voyage can be embarked on
+    pub embark_dl: u32,        //last block the voyage can be embarked on
+    pub sail_dur: u32,         //number of blocks voyages sail for after the embark deadline
+    pub price_mode: PriceMode, //how the price decays over the embark window
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum PriceMode {
+    Fixed,           // voyages always cost the offering price
+    Linear(BigInt), // price decays linearly from the offering price to the reserve price at the embark deadline
+    Expon(ExpDecay), // price premium over the reserve price halves every half life
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ExpDecay {
+    pub reserve: BigInt, //price the auction decays towards in voyage asset
+    pub half_life: u32,  //number of blocks it takes the premium over the reserve price to halve
+}
+
+#[derive(Clone)]
+#[contracttype]
+// layout of voyages stored before offerings had schedules, read by `get_voyage` with default schedules
+pub struct VoyageInfoV1 {
     pub vyg_asset: BytesN<32>, //asset being used to fund the voyage
     pub price: BigInt,         //the cost to embark on a voyage in voyage asset
     pub max_vygs: BigInt,      //max number of voyages that can be entered for doubloons
@@ -408,3 +2536,226 @@ pub struct VoyageKey {
     pub user_id: Identifier, //user public key
     pub voyage_id: i32,      //id of the voyage entered by the user
 }
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RaidCommit {
+    pub raider: Identifier,  //user performing the raid
+    pub voyage_id: i32,      //id of the voyage being raided
+    pub voyager: Identifier, //user being raided
+    pub n_vygs: BigInt,      //number of voyages the raid was paid for
+    pub cost: BigInt,        //doubloons burned to pay for the raid
+    pub hash: BytesN<32>,    //sha256 hash of the raider's secret
+    pub odds: u32,           //raid probability (scaled by 10000) locked in at commit time
+    pub ledger: u32,         //ledger the raid was committed on
+    pub beacon: BytesN<32>,  //randomness from the oracle, zero until it arrives
+    pub drawn: u32,          //ledger the oracle's randomness arrived on, 0 until it arrives
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RaidRequest {
+    pub raider: Identifier,  //user performing the raid
+    pub voyage_id: i32,      //id of the voyage being raided
+    pub voyager: Identifier, //user being raided
+    pub n_vygs: BigInt,      //number of voyages the raid was paid for
+    pub cost: BigInt,        //doubloons burned to pay for the raid
+    pub odds: u32,           //raid probability (scaled by 10000) locked in at request time
+    pub ledger: u32,         //ledger the randomness was requested on
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RaidQuote {
+    pub n_vygs: BigInt, //number of voyages that would be raided
+    pub cost: BigInt,   //doubloons burned from the raider to pay for the raid
+    pub odds: u32,      //raid probability (scaled by 10000) that would be locked in
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct VoyageStatus {
+    pub n_vygs: BigInt, //number of voyages the user is on
+    pub matures: u32,   //first block the position can be redeemed without penalty
+    pub mature: bool,   //whether the position can be redeemed without penalty
+    pub payout: BigInt, //doubloons paid out for the position once it completes
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BondPosition {
+    pub payout: BigInt, //doubloons left to be paid out
+    pub vesting: u32,   //blocks left until the position is fully vested, as of last_ldgr
+    pub last_ldgr: u32, //block the position was last claimed or added to
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BondInfo {
+    pub payout: BigInt,    //doubloons left to be paid out
+    pub vesting: u32,      //blocks left until the position is fully vested, as of last_ldgr
+    pub last_ldgr: u32,    //block the position was last claimed or added to
+    pub claimable: BigInt, //doubloons that can be claimed now
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PriceCtrl {
+    pub bcv: BigInt,     //price in the voyage asset at a debt ratio of 100%
+    pub floor: BigInt,   //minimum price of a voyage in the voyage asset
+    pub debt: BigInt, //doubloon payout sold by the offering that hasn't decayed, as of last_decay
+    pub decay_len: u32, //number of blocks debt decays to zero over
+    pub last_decay: u32, //block the debt was last decayed at
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceKey {
+    pub from: Identifier,    //user whose buried doubloons are being spent
+    pub spender: Identifier, //user allowed to spend them
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct QueueEntry {
+    pub amount: BigInt, //doubloons waiting in the queue
+    pub ready: u32,     //first block the entry can be claimed
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CapnProposal {
+    pub nominee: Identifier, //address proposed as the next captain
+    pub expires: u32,        //last block the proposal can be accepted on
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum Role {
+    VygMaster,  // creates voyage and bond offerings
+    Treasurer,  // moves treasury holdings
+    RateKeeper, // sets the rebase rate and target raid interval
+    Guardian,   // pauses and unpauses the sea
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RoleKey {
+    pub role: Role,     //role being held
+    pub id: Identifier, //identity holding it
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Council {
+    pub members: Vec<Identifier>, //addresses on the council
+    pub threshold: u32,           //number of member approvals an action needs
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ActionProp {
+    pub action: AdminAction,        //captain only call to make
+    pub approvals: Vec<Identifier>, //addresses that approved the action
+    pub expires: u32,               //last block the action can be approved or executed on
+}
+
+#[derive(Clone)]
+#[contracttype]
+// a captain only call, named after its entry point, that the council can vote on
+pub enum AdminAction {
+    SetEpoch(u32),
+    SetBounty(BigInt),
+    SetRfv(RfvArgs),
+    SetRwdCap(BigInt),
+    SetWarmup(u32),
+    SetCooldn(u32),
+    FixVyg(FixArgs),
+    SetExitP(BigInt),
+    SetCtrl(CtrlArgs),
+    SetOracle(BytesN<32>),
+    SetWrap(BytesN<32>),
+    PropCapn(Identifier),
+    CnclCapn,
+    RnceCapn,
+    GrantRole(RoleKey),
+    RvkeRole(RoleKey),
+    SetCouncil(Council),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RfvArgs {
+    pub asset: BytesN<32>, //address of the voyage asset
+    pub value: BigInt, //doubloons one unit of the asset is worth, scaled by the contract decimals
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct FixArgs {
+    pub voyage_id: i32,                     //id of the voyage offering being repaired
+    pub positions: Map<Identifier, BigInt>, //correct open positions of every user on the voyage, zero deletes a position
+    pub closed: BigInt,                     //voyages already redeemed or plundered
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CtrlArgs {
+    pub voyage_id: i32, //id of the offering
+    pub bcv: BigInt,    //price in the voyage asset at a debt ratio of 100%
+    pub floor: BigInt,  //minimum price of a voyage in the voyage asset
+    pub decay_len: u32, //number of blocks the offering's debt decays to zero over
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct LockPosition {
+    pub amount: BigInt, //doubloons buried under the lock
+    pub shares: BigInt, //boosted buried shares held by the lock
+    pub boost: BigInt,  //rebase reward boost, scaled by the contract decimals
+    pub unlock: u32,    //first block the lock can be unlocked
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ProtocolState {
+    pub base_token: BytesN<32>, //address of the doubloon token
+    pub captain: Identifier,    //address of the captain
+    pub decimals: u32,          //decimals for the index, rate and buried doubloons
+    pub rate: BigInt,           //per epoch rebase rate, scaled by the contract decimals
+    pub index: BigInt,          //rebase index projected to the current block
+    pub last_ldgr: u32,         //block the stored index was last updated at
+    pub epoch: EpochInfo,       //rebase epoch information
+    pub bounty: BigInt,         //doubloons paid to the caller of `rebase`
+    pub ttl_buried: BigInt, //total buried doubloon shares, including the boosted shares of locks
+    pub ttl_queued: BigInt, //doubloons waiting in warmup and cooldown queues
+    pub supply: BigInt,     //doubloons minted by the sea less those it burned
+    pub excess_rsv: BigInt, //treasury reserves in excess of the doubloons the sea owes
+    pub warmup: u32, //number of blocks newly buried doubloons wait before they can be claimed
+    pub cooldown: u32, //number of blocks unearthed doubloons wait before they can be claimed
+    pub exit_pen: BigInt, //penalty taken from voyages ended before they complete
+    pub tgt_raid: u32, //target number of blocks between raids
+    pub last_raid: u32, //last block a raid occurred on
+    pub last_vyg: i32, //id of the last voyage offering
+    pub paused: bool, //whether the guardian has paused the sea
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct UserState {
+    pub doubloons: BigInt,               //doubloon balance
+    pub buried: BigInt,                  //buried doubloon shares
+    pub value: BigInt, //value of the buried shares in doubloons at the current index
+    pub locked: BigInt, //value of the lock positions in doubloons at the current index
+    pub queued: BigInt, //doubloons waiting in the warmup and cooldown queues
+    pub voyages: Map<i32, VoyageStatus>, //status of every voyage the user is on, by voyage id
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct EpochInfo {
+    pub number: u32,    //number of epochs settled
+    pub length: u32,    //number of blocks in an epoch
+    pub next_ldgr: u32, //block the next epoch can be settled at
+    pub pending: u32,   //number of epochs that can be settled now
+}
@@ -1,11 +1,17 @@
+#![allow(dead_code)]
+
 use soroban_bag::{
-    seven_seas::{SevenSeas, SevenSeasClient},
+    seven_seas::{PriceMode, SevenSeas, SevenSeasClient},
     token,
 };
 
 use rand::{thread_rng, RngCore};
-use soroban_auth::Identifier;
-use soroban_sdk::{AccountId, BytesN, Env, IntoVal};
+use soroban_auth::{Identifier, Signature};
+use soroban_sdk::{
+    contractimpl,
+    testutils::{Accounts, Ledger, LedgerInfo},
+    AccountId, BigInt, BytesN, Env, IntoVal,
+};
 
 pub const SCALER: i64 = 10000000;
 
@@ -53,7 +59,238 @@ pub fn create_base_token_contract(
     token
 }
 
+pub fn create_wrapped_token_contract(
+    e: &Env,
+    contract_id: &BytesN<32>,
+    admin: &AccountId,
+) -> token::Client {
+    e.register_contract_token(contract_id);
+
+    let token = token::Client::new(e, contract_id);
+    token.init(
+        &Identifier::Account(admin.clone()),
+        &token::TokenMetadata {
+            name: "wrapped buried doubloon".into_val(e),
+            symbol: "wsDOUBLOON".into_val(e),
+            decimals: 7,
+        },
+    );
+    token
+}
+
 pub fn create_sea_contract(e: &Env, contract_id: &BytesN<32>) -> SevenSeasClient {
     e.register_contract(contract_id, SevenSeas {});
     return SevenSeasClient::new(e, contract_id);
 }
+
+pub struct VoyageSetup {
+    pub token_admin: AccountId,
+    pub sea_contract_id: BytesN<32>,
+    pub sea_id: Identifier,
+    pub sea_client: SevenSeasClient,
+    pub base_token_client: token::Client,
+    pub usdc_token_contract_id: BytesN<32>,
+    pub usdc_token_client: token::Client,
+    pub vyg_price: BigInt,
+    pub user1_acct: AccountId,
+    pub user1_id: Identifier,
+    pub user2_acct: AccountId,
+    pub user2_id: Identifier,
+    pub voyage_id: i32,
+    pub user_num_voyages: BigInt,
+}
+
+pub fn set_sequence(e: &Env, sequence_number: u32) {
+    e.ledger().set(LedgerInfo {
+        timestamp: 100,
+        protocol_version: 1,
+        sequence_number,
+        network_passphrase: Default::default(),
+        base_reserve: 10,
+    });
+}
+
+// creates a voyage, embarks on it with user1, funds user2 with enough doubloons to raid and lets 900 blocks pass
+pub fn setup_voyage(e: &Env) -> VoyageSetup {
+    //set ledger sequence so we can estimate voyage expiration
+    e.ledger().set(LedgerInfo {
+        timestamp: 100,
+        protocol_version: 1,
+        sequence_number: 10,
+        network_passphrase: Default::default(),
+        base_reserve: 10,
+    });
+
+    let max_vygs = BigInt::from_i64(&e, 1000);
+    let vyg_price = BigInt::from_i64(&e, 10);
+    let user_num_voyages = BigInt::from_i64(&e, 100);
+    let user_usdc_spend = vyg_price.clone() * user_num_voyages.clone();
+
+    // deploy token contracts
+    let token_admin = e.accounts().generate_and_create();
+    let usdc_token_contract_id = generate_contract_id(&e);
+    let base_token_contract_id = generate_contract_id(&e);
+    let usdc_token_client = create_usdc_token_contract(&e, &usdc_token_contract_id, &token_admin);
+    let base_token_client = create_base_token_contract(&e, &base_token_contract_id, &token_admin);
+
+    // setup env
+    let user1_acct = e.accounts().generate_and_create();
+    let user1_id = Identifier::Account(user1_acct.clone());
+    usdc_token_client.with_source_account(&token_admin).mint(
+        &soroban_auth::Signature::Invoker,
+        &BigInt::zero(&e),
+        &user1_id,
+        &user_usdc_spend,
+    );
+    let user2_acct = e.accounts().generate_and_create();
+    let user2_id = Identifier::Account(user2_acct.clone());
+    base_token_client.with_source_account(&token_admin).mint(
+        &soroban_auth::Signature::Invoker,
+        &BigInt::zero(&e),
+        &user2_id,
+        &BigInt::from_i64(&e, SCALER),
+    );
+    assert_eq!(usdc_token_client.balance(&user1_id), user_usdc_spend);
+    // deploy and init sea
+    let rate = BigInt::from_i64(&e, 5);
+    let target_raid_interval: u32 = 1800;
+    let sea_contract_id = generate_contract_id(&e);
+    let sea_id = Identifier::Contract(sea_contract_id.clone());
+    let sea_client = create_sea_contract(&e, &sea_contract_id);
+    sea_client.with_source_account(&token_admin).initialize(
+        &base_token_contract_id,
+        &rate,
+        &target_raid_interval,
+    );
+
+    // transfer admin priviliges
+    base_token_client
+        .with_source_account(&token_admin)
+        .set_admin(&Signature::Invoker, &BigInt::zero(&e), &sea_id);
+
+    // create voyage
+    sea_client.with_source_account(&token_admin).new_voyage(
+        &usdc_token_contract_id,
+        &vyg_price,
+        &max_vygs,
+        &BigInt::from_i64(&e, SCALER),
+        &10,
+        &(10 + 100700),
+        &100,
+        &PriceMode::Fixed,
+    );
+
+    // enter voyage with user
+    usdc_token_client.with_source_account(&user1_acct).approve(
+        &Signature::Invoker,
+        &BigInt::zero(&e),
+        &sea_id,
+        &user_usdc_spend,
+    );
+    let voyage_id: i32 = 1;
+    sea_client
+        .with_source_account(&user1_acct)
+        .voyage(&voyage_id, &user_num_voyages, &vyg_price);
+
+    //let time pass - raid probability is 910/1800 of 1.25%
+    set_sequence(e, 10 + 900);
+
+    VoyageSetup {
+        token_admin,
+        sea_contract_id,
+        sea_id,
+        sea_client,
+        base_token_client,
+        usdc_token_contract_id,
+        usdc_token_client,
+        vyg_price,
+        user1_acct,
+        user1_id,
+        user2_acct,
+        user2_id,
+        voyage_id,
+        user_num_voyages,
+    }
+}
+
+// funds the user with usdc and embarks on the voyage
+pub fn embark(
+    e: &Env,
+    setup: &VoyageSetup,
+    user_acct: &AccountId,
+    voyage_id: i32,
+    num_voyages: &BigInt,
+) {
+    let user_id = Identifier::Account(user_acct.clone());
+    let usdc_spend = setup.vyg_price.clone() * num_voyages.clone();
+    setup
+        .usdc_token_client
+        .with_source_account(&setup.token_admin)
+        .mint(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &user_id,
+            &usdc_spend,
+        );
+    setup
+        .usdc_token_client
+        .with_source_account(user_acct)
+        .approve(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.sea_id,
+            &usdc_spend,
+        );
+    setup.sea_client.with_source_account(user_acct).voyage(
+        &voyage_id,
+        num_voyages,
+        &setup.vyg_price,
+    );
+}
+
+// minimal randomness oracle - records requests and lets the test pick the randomness
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn request(e: Env, request_id: u32) {
+        e.data().set(request_id, true);
+    }
+
+    pub fn fulfill(e: Env, sea_id: BytesN<32>, request_id: u32, randomness: BytesN<32>) {
+        if !e.data().has(request_id) {
+            panic!("unknown request");
+        }
+        e.data().remove(request_id);
+        SevenSeasClient::new(&e, &sea_id).fulfill_rd(&request_id, &randomness);
+    }
+}
+
+// registers the mock oracle and sets it as the sea's oracle
+pub fn setup_oracle(e: &Env, setup: &VoyageSetup) -> MockOracleClient {
+    let oracle_contract_id = generate_contract_id(e);
+    e.register_contract(&oracle_contract_id, MockOracle {});
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_oracle(&oracle_contract_id);
+    MockOracleClient::new(e, &oracle_contract_id)
+}
+
+// values the usdc paid for user1's voyages so highly that the excess reserves never cap the rebase
+pub fn back_rewards(e: &Env, setup: &VoyageSetup) {
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rfv(
+            &setup.usdc_token_contract_id,
+            &BigInt::from_i64(&e, 1000000 * SCALER),
+        );
+}
+
+// oracle randomness that is zero apart from its last byte
+pub fn randomness(e: &Env, last_byte: u8) -> BytesN<32> {
+    let mut bytes = [0u8; 32];
+    bytes[31] = last_byte;
+    BytesN::from_array(e, &bytes)
+}
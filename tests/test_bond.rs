@@ -0,0 +1,241 @@
+#![cfg(test)]
+
+use soroban_auth::{Identifier, Signature};
+use soroban_bag::seven_seas::PriceMode;
+use soroban_sdk::{testutils::Accounts, AccountId, BigInt, Env};
+mod helper;
+use helper::{embark, set_sequence, setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+
+// creates a bond market paying 2 doubloons per bond, vesting over 100 blocks, and returns its id
+fn create_bond(e: &Env, setup: &VoyageSetup) -> i32 {
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_bond(
+            &setup.usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, 2 * SCALER),
+            &910,
+            &1910,
+            &100,
+            &PriceMode::Fixed,
+        );
+    2
+}
+
+// funds the user with usdc and buys bonds
+fn buy_bond(e: &Env, setup: &VoyageSetup, user_acct: &AccountId, bond_id: i32, num: &BigInt) {
+    let user_id = Identifier::Account(user_acct.clone());
+    let usdc_spend = setup.vyg_price.clone() * num.clone();
+    setup
+        .usdc_token_client
+        .with_source_account(&setup.token_admin)
+        .mint(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &user_id,
+            &usdc_spend,
+        );
+    setup
+        .usdc_token_client
+        .with_source_account(user_acct)
+        .approve(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.sea_id,
+            &usdc_spend,
+        );
+    setup
+        .sea_client
+        .with_source_account(user_acct)
+        .bond(&bond_id, num, &setup.vyg_price);
+}
+
+#[test]
+fn test_bond_vests_linearly() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let bond_id = create_bond(&e, &setup);
+    let user3_acct = e.accounts().generate_and_create();
+    let user3_id = Identifier::Account(user3_acct.clone());
+
+    buy_bond(&e, &setup, &user3_acct, bond_id, &BigInt::from_i64(&e, 10));
+
+    // the treasury asset is taken and the bond is closed on the offering right away
+    assert_eq!(
+        setup.usdc_token_client.balance(&setup.sea_id),
+        setup.vyg_price.clone() * BigInt::from_i64(&e, 110)
+    );
+    assert_eq!(
+        setup.sea_client.get_voyage(&bond_id).n_embarked,
+        BigInt::from_i64(&e, 10)
+    );
+    assert!(setup.sea_client.recon_vyg(&bond_id));
+    let info = setup.sea_client.bond_info(&user3_id, &bond_id);
+    assert_eq!(info.payout, BigInt::from_i64(&e, 20 * SCALER));
+    assert_eq!(info.vesting, 100);
+    assert_eq!(info.last_ldgr, 910);
+    assert_eq!(info.claimable, BigInt::zero(&e));
+
+    // a quarter of the way through vesting
+    set_sequence(&e, 935);
+    assert_eq!(
+        setup.sea_client.bond_info(&user3_id, &bond_id).claimable,
+        BigInt::from_i64(&e, 5 * SCALER)
+    );
+    let claimed = setup
+        .sea_client
+        .with_source_account(&user3_acct)
+        .claim_vest(&bond_id);
+    assert_eq!(claimed, BigInt::from_i64(&e, 5 * SCALER));
+    assert_eq!(
+        setup.base_token_client.balance(&user3_id),
+        BigInt::from_i64(&e, 5 * SCALER)
+    );
+    let info = setup.sea_client.bond_info(&user3_id, &bond_id);
+    assert_eq!(info.payout, BigInt::from_i64(&e, 15 * SCALER));
+    assert_eq!(info.vesting, 75);
+    assert_eq!(info.last_ldgr, 935);
+
+    // the rest vests by the end of the original vesting period
+    set_sequence(&e, 1010);
+    setup
+        .sea_client
+        .with_source_account(&user3_acct)
+        .claim_vest(&bond_id);
+    assert_eq!(
+        setup.base_token_client.balance(&user3_id),
+        BigInt::from_i64(&e, 20 * SCALER)
+    );
+    assert_eq!(
+        setup.sea_client.bond_info(&user3_id, &bond_id).payout,
+        BigInt::zero(&e)
+    );
+}
+
+#[test]
+fn test_bond_again_restarts_vesting() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let bond_id = create_bond(&e, &setup);
+    let user3_acct = e.accounts().generate_and_create();
+    let user3_id = Identifier::Account(user3_acct.clone());
+
+    buy_bond(&e, &setup, &user3_acct, bond_id, &BigInt::from_i64(&e, 10));
+    set_sequence(&e, 960);
+    buy_bond(&e, &setup, &user3_acct, bond_id, &BigInt::from_i64(&e, 5));
+
+    // half of the first bond is claimed, the rest is merged with the new bond and vests from now
+    assert_eq!(
+        setup.base_token_client.balance(&user3_id),
+        BigInt::from_i64(&e, 10 * SCALER)
+    );
+    let info = setup.sea_client.bond_info(&user3_id, &bond_id);
+    assert_eq!(info.payout, BigInt::from_i64(&e, 20 * SCALER));
+    assert_eq!(info.vesting, 100);
+    assert_eq!(info.last_ldgr, 960);
+}
+
+#[test]
+fn test_bond_dynamic_price() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let bond_id = create_bond(&e, &setup);
+    let user3_acct = e.accounts().generate_and_create();
+    let user3_id = Identifier::Account(user3_acct.clone());
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_ctrl(
+            &bond_id,
+            &BigInt::from_i64(&e, 10),
+            &BigInt::from_i64(&e, 5),
+            &100,
+        );
+
+    // no doubloons have been minted by the sea yet, so the bond sells at the floor
+    assert_eq!(
+        setup.sea_client.cur_price(&bond_id),
+        BigInt::from_i64(&e, 5)
+    );
+    buy_bond(&e, &setup, &user3_acct, bond_id, &BigInt::from_i64(&e, 10));
+    assert_eq!(
+        setup.usdc_token_client.balance(&user3_id),
+        BigInt::from_i64(&e, 50)
+    );
+    // the debt has nothing outstanding to spread over, so it counts as a ratio of 1
+    assert_eq!(
+        setup.sea_client.cur_price(&bond_id),
+        BigInt::from_i64(&e, 10)
+    );
+
+    // 5 doubloons vest and are minted, while a quarter of the 20 doubloons of debt decays
+    set_sequence(&e, 935);
+    setup
+        .sea_client
+        .with_source_account(&user3_acct)
+        .claim_vest(&bond_id);
+    // debt ratio is 15 / 5 = 3, so the price is 10 * 3
+    assert_eq!(
+        setup.sea_client.cur_price(&bond_id),
+        BigInt::from_i64(&e, 30)
+    );
+    // burying doubloons minted elsewhere doesn't shrink what the sea owes
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+    assert_eq!(
+        setup.sea_client.cur_price(&bond_id),
+        BigInt::from_i64(&e, 30)
+    );
+
+    // once the debt has decayed the price is back at the floor
+    set_sequence(&e, 1010);
+    assert_eq!(
+        setup.sea_client.cur_price(&bond_id),
+        BigInt::from_i64(&e, 5)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(26)")]
+fn test_voyage_on_bond_market() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let bond_id = create_bond(&e, &setup);
+    let user3_acct = e.accounts().generate_and_create();
+
+    embark(&e, &setup, &user3_acct, bond_id, &BigInt::from_i64(&e, 10));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(27)")]
+fn test_bond_on_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user3_acct = e.accounts().generate_and_create();
+
+    buy_bond(
+        &e,
+        &setup,
+        &user3_acct,
+        setup.voyage_id,
+        &BigInt::from_i64(&e, 10),
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(29)")]
+fn test_claim_without_bond() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let bond_id = create_bond(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .claim_vest(&bond_id);
+}
@@ -0,0 +1,191 @@
+#![cfg(test)]
+
+use soroban_auth::Signature;
+use soroban_sdk::{BigInt, Bytes, Env};
+mod helper;
+use helper::{back_rewards, set_sequence, setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+
+// backs the rebase and buries all of user2's doubloons, at block 910 the index is 1.0000045
+fn bury(e: &Env, setup: &VoyageSetup) {
+    back_rewards(&e, &setup);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+}
+
+#[test]
+fn test_balance_rebases() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    bury(&e, &setup);
+
+    // 9999955 shares are worth just under the buried amount after rounding
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::from_i64(&e, 9999955)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 9999999)
+    );
+
+    // the balance grows with the index while the shares stay put
+    set_sequence(&e, 1910);
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10000049)
+    );
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::from_i64(&e, 9999955)
+    );
+}
+
+#[test]
+fn test_xfer() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    bury(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .xfer(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user1_id,
+            &BigInt::from_i64(&e, 5000000),
+        );
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user1_id),
+        BigInt::from_i64(&e, 4999977)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user1_id),
+        BigInt::from_i64(&e, 4999999)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 5000000)
+    );
+
+    // both holders earn the rebase
+    set_sequence(&e, 1910);
+    assert_eq!(
+        setup.sea_client.balance(&setup.user1_id),
+        BigInt::from_i64(&e, 5000024)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 5000025)
+    );
+}
+
+#[test]
+fn test_xfer_from() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    bury(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .approve(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user1_id,
+            &BigInt::from_i64(&e, 3000000),
+        );
+    assert_eq!(
+        setup.sea_client.allowance(&setup.user2_id, &setup.user1_id),
+        BigInt::from_i64(&e, 3000000)
+    );
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .xfer_from(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user2_id,
+            &setup.user1_id,
+            &BigInt::from_i64(&e, 2000000),
+        );
+    assert_eq!(
+        setup.sea_client.allowance(&setup.user2_id, &setup.user1_id),
+        BigInt::from_i64(&e, 1000000)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user1_id),
+        BigInt::from_i64(&e, 1999999)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 7999999)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(41)")]
+fn test_xfer_from_over_allowance() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    bury(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .approve(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user1_id,
+            &BigInt::from_i64(&e, 1000000),
+        );
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .xfer_from(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user2_id,
+            &setup.user1_id,
+            &BigInt::from_i64(&e, 2000000),
+        );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(40)")]
+fn test_xfer_over_balance() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    bury(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .xfer(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user1_id,
+            &BigInt::from_i64(&e, 2 * SCALER),
+        );
+}
+
+#[test]
+fn test_metadata() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    assert_eq!(setup.sea_client.decimals(), 7);
+    assert_eq!(
+        setup.sea_client.name(),
+        Bytes::from_slice(&e, b"buried doubloon")
+    );
+    assert_eq!(
+        setup.sea_client.symbol(),
+        Bytes::from_slice(&e, b"sDOUBLOON")
+    );
+    assert_eq!(setup.sea_client.nonce(&setup.user2_id), BigInt::zero(&e));
+}
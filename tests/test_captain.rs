@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+use soroban_auth::Identifier;
+use soroban_bag::seven_seas::HANDOVER_WINDOW;
+use soroban_sdk::Env;
+mod helper;
+use helper::{set_sequence, setup_voyage};
+extern crate std;
+
+#[test]
+fn test_handover() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+
+    let expires = captain.prop_capn(&setup.user1_id);
+    assert_eq!(expires, 910 + HANDOVER_WINDOW);
+    let proposal = setup.sea_client.capn_prop();
+    assert_eq!(proposal.nominee, setup.user1_id);
+    assert_eq!(proposal.expires, expires);
+    // the captain keeps control until the nominee accepts
+    assert_eq!(
+        setup.sea_client.prot_state().captain,
+        Identifier::Account(setup.token_admin.clone())
+    );
+
+    set_sequence(&e, expires);
+    let new_captain = setup.sea_client.with_source_account(&setup.user1_acct);
+    new_captain.acpt_capn();
+    assert_eq!(setup.sea_client.prot_state().captain, setup.user1_id);
+    new_captain.set_cooldn(&10);
+    assert_eq!(setup.sea_client.prot_state().cooldown, 10);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(1)")]
+fn test_old_captain_replaced() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.prop_capn(&setup.user1_id);
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .acpt_capn();
+
+    captain.set_cooldn(&10);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(6)")]
+fn test_accept_not_nominee() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .prop_capn(&setup.user1_id);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .acpt_capn();
+}
+
+#[test]
+#[should_panic(expected = "ContractError(7)")]
+fn test_accept_expired() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let expires = setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .prop_capn(&setup.user1_id);
+
+    set_sequence(&e, expires + 1);
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .acpt_capn();
+}
+
+#[test]
+#[should_panic(expected = "ContractError(5)")]
+fn test_accept_cancelled() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.prop_capn(&setup.user1_id);
+    captain.cncl_capn();
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .acpt_capn();
+}
+
+#[test]
+#[should_panic(expected = "ContractError(1)")]
+fn test_renounce() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.rnce_capn();
+    assert_eq!(setup.sea_client.prot_state().captain, setup.sea_id);
+
+    captain.set_cooldn(&10);
+}
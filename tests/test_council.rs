@@ -0,0 +1,208 @@
+#![cfg(test)]
+
+use soroban_auth::Identifier;
+use soroban_bag::seven_seas::{AdminAction, Council, Role, HANDOVER_WINDOW};
+use soroban_sdk::{testutils::Accounts, vec, AccountId, BigInt, Env};
+mod helper;
+use helper::{set_sequence, setup_voyage, VoyageSetup};
+extern crate std;
+
+// hands the captaincy to a 2 of 3 council of user1, user2 and a new account, and returns the new account
+fn setup_council(e: &Env, setup: &VoyageSetup) -> AccountId {
+    let user3_acct = e.accounts().generate_and_create();
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_cncl(
+            &vec![
+                e,
+                setup.user1_id.clone(),
+                setup.user2_id.clone(),
+                Identifier::Account(user3_acct.clone()),
+            ],
+            &2,
+        );
+    user3_acct
+}
+
+#[test]
+fn test_council_executes() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+    assert_eq!(setup.sea_client.prot_state().captain, setup.sea_id);
+    assert_eq!(setup.sea_client.council().threshold, 2);
+
+    let user1 = setup.sea_client.with_source_account(&setup.user1_acct);
+    let action_id = user1.prop_act(&AdminAction::SetCooldn(100));
+    assert_eq!(
+        setup.sea_client.get_action(&action_id).approvals,
+        vec![&e, setup.user1_id.clone()]
+    );
+    let approvals = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .aprv_act(&action_id);
+    assert_eq!(approvals, 2);
+
+    user1.exec_act(&action_id);
+    assert_eq!(setup.sea_client.prot_state().cooldown, 100);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(1)")]
+fn test_council_replaces_captain() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_cooldn(&100);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(8)")]
+fn test_council_revokes_captain_roles() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+    assert!(!setup.sea_client.has_role(
+        &Role::Guardian,
+        &Identifier::Account(setup.token_admin.clone())
+    ));
+
+    // the old captain can't move the treasury around the council
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .xfer_held(
+            &setup.usdc_token_contract_id,
+            &setup.user2_id,
+            &BigInt::from_i64(&e, 400),
+        );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(85)")]
+fn test_council_below_threshold() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+
+    let user1 = setup.sea_client.with_source_account(&setup.user1_acct);
+    let action_id = user1.prop_act(&AdminAction::SetCooldn(100));
+    user1.exec_act(&action_id);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(84)")]
+fn test_council_approve_twice() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+
+    let user1 = setup.sea_client.with_source_account(&setup.user1_acct);
+    let action_id = user1.prop_act(&AdminAction::SetCooldn(100));
+    user1.aprv_act(&action_id);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(81)")]
+fn test_council_not_member() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .prop_act(&AdminAction::SetCooldn(100));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(82)")]
+fn test_council_duplicate_members() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_cncl(
+            &vec![&e, setup.user1_id.clone(), setup.user1_id.clone()],
+            &2,
+        );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(7)")]
+fn test_council_action_expired() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_council(&e, &setup);
+
+    let action_id = setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .prop_act(&AdminAction::SetCooldn(100));
+    set_sequence(&e, 910 + HANDOVER_WINDOW + 1);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .aprv_act(&action_id);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(85)")]
+fn test_council_removed_member_approval() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user3_acct = setup_council(&e, &setup);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+    let user3 = setup.sea_client.with_source_account(&user3_acct);
+    let pending = setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .prop_act(&AdminAction::SetCooldn(100));
+
+    // the members can only be changed by a council action, here voting user1 off
+    let council = Council {
+        members: vec![
+            &e,
+            setup.user2_id.clone(),
+            Identifier::Account(user3_acct.clone()),
+        ],
+        threshold: 2,
+    };
+    let action_id = user2.prop_act(&AdminAction::SetCouncil(council));
+    user3.aprv_act(&action_id);
+    user3.exec_act(&action_id);
+    assert_eq!(setup.sea_client.council().members.len(), 2);
+
+    // user1's approval no longer counts towards the pending action
+    assert_eq!(user2.aprv_act(&pending), 1);
+    user2.exec_act(&pending);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(80)")]
+fn test_council_hands_over() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user3_acct = setup_council(&e, &setup);
+
+    let action_id = setup
+        .sea_client
+        .with_source_account(&user3_acct)
+        .prop_act(&AdminAction::PropCapn(setup.user1_id.clone()));
+    let user1 = setup.sea_client.with_source_account(&setup.user1_acct);
+    user1.aprv_act(&action_id);
+    user1.exec_act(&action_id);
+    user1.acpt_capn();
+    assert_eq!(setup.sea_client.prot_state().captain, setup.user1_id);
+
+    // the council is dissolved once it hands over the captaincy
+    setup.sea_client.council();
+}
@@ -71,7 +71,7 @@ fn test_deposit_happy_path() {
         .with_source_account(&user1_acct)
         .bury(&deposit_amount);
 
-    let expected_bury_amount = BigInt::from_i32(&e, 122842576);
+    let expected_bury_amount = BigInt::from_i32(&e, 122841061);
     assert_eq!(base_token_client.balance(&user1_id), BigInt::zero(&e));
     assert_eq!(base_token_client.balance(&sea_id), BigInt::zero(&e));
     assert_eq!(sea_client.get_buried(&user1_id), expected_bury_amount);
@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use soroban_auth::Identifier;
+use soroban_sdk::{symbol, testutils::Events, vec, BigInt, BytesN, Env, IntoVal, RawVal, Vec};
+mod helper;
+use helper::{set_sequence, setup_voyage, SCALER};
+extern crate std;
+
+// checks that the last event published is the expected sea event
+fn assert_last_event(e: &Env, sea_contract_id: &BytesN<32>, topics: Vec<RawVal>, data: RawVal) {
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![e, (sea_contract_id.clone(), topics, data)]
+    );
+}
+
+#[test]
+fn test_bury_events() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+
+    user2.bury(&BigInt::from_i64(&e, SCALER));
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("bury"), setup.user2_id.clone()).into_val(&e),
+        (BigInt::from_i64(&e, SCALER), BigInt::from_i64(&e, 9999955)).into_val(&e),
+    );
+
+    user2.unearth(&BigInt::from_i64(&e, 9999955));
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("unearth"), setup.user2_id.clone()).into_val(&e),
+        (BigInt::from_i64(&e, 9999955), BigInt::from_i64(&e, 9999999)).into_val(&e),
+    );
+}
+
+#[test]
+fn test_voyage_events() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // embarking in the setup paid 10 usdc for each of the 100 voyages
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("voyage"), setup.user1_id.clone(), setup.voyage_id).into_val(&e),
+        (setup.user_num_voyages.clone(), BigInt::from_i64(&e, 1000)).into_val(&e),
+    );
+
+    set_sequence(&e, 100811);
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&setup.voyage_id);
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (
+            symbol!("end_voyage"),
+            setup.user1_id.clone(),
+            setup.voyage_id,
+        )
+            .into_val(&e),
+        (
+            setup.user_num_voyages.clone(),
+            BigInt::from_i64(&e, 100 * SCALER),
+        )
+            .into_val(&e),
+    );
+}
+
+#[test]
+fn test_captain_events() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain_id = Identifier::Account(setup.token_admin.clone());
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+
+    captain.set_rate(&BigInt::from_i64(&e, 50000));
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("set_rate"), captain_id.clone()).into_val(&e),
+        BigInt::from_i64(&e, 50000).into_val(&e),
+    );
+
+    // the handover is published under the old captain, later changes under the new one
+    captain.prop_capn(&setup.user1_id);
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("prop_capn"), captain_id.clone()).into_val(&e),
+        (setup.user1_id.clone(), 910_u32 + 100800).into_val(&e),
+    );
+    let new_captain = setup.sea_client.with_source_account(&setup.user1_acct);
+    new_captain.acpt_capn();
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("acpt_capn"), captain_id).into_val(&e),
+        setup.user1_id.clone().into_val(&e),
+    );
+    new_captain.set_cooldn(&900);
+    assert_last_event(
+        &e,
+        &setup.sea_contract_id,
+        (symbol!("set_cooldn"), setup.user1_id.clone()).into_val(&e),
+        900_u32.into_val(&e),
+    );
+}
@@ -0,0 +1,220 @@
+#![cfg(test)]
+
+use soroban_auth::Signature;
+use soroban_bag::{
+    indexer::SeaModel,
+    seven_seas::{AdminAction, Role},
+};
+use soroban_sdk::{
+    testutils::Events,
+    vec,
+    xdr::{
+        ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ExtensionPoint, Hash,
+        ScVal, ScVec,
+    },
+    BigInt, BytesN, Env, TryFromVal,
+};
+mod helper;
+use helper::{randomness, set_sequence, setup_oracle, setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+use std::vec::Vec as StdVec;
+
+// returns the events published so far as the XDR an indexer reads from transaction metadata
+fn xdr_events(e: &Env) -> StdVec<ContractEvent> {
+    let mut events = StdVec::new();
+    for event in e.events().all().iter() {
+        let (contract_id, topics, data) = event.unwrap();
+        let topics: StdVec<ScVal> = topics
+            .iter()
+            .map(|t| ScVal::try_from_val(e, t.unwrap()).unwrap())
+            .collect();
+        events.push(ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: Some(Hash(contract_id.to_array())),
+            type_: ContractEventType::Contract,
+            body: ContractEventBody::V0(ContractEventV0 {
+                topics: ScVec(topics.try_into().unwrap()),
+                data: ScVal::try_from_val(e, data).unwrap(),
+            }),
+        });
+    }
+    events
+}
+
+// rebuilds the sea from its events and checks the model against the contract's views
+fn assert_model_matches(e: &Env, setup: &VoyageSetup) -> SeaModel {
+    let mut model = SeaModel::new(e);
+    model.replay(&Hash(setup.sea_contract_id.to_array()), &xdr_events(e));
+
+    let state = setup.sea_client.prot_state();
+    assert!(model.captain == Some(state.captain));
+    assert_eq!(model.rate, state.rate);
+    assert_eq!(model.epoch, state.epoch.number);
+    if state.epoch.pending == 0 {
+        assert_eq!(model.index, state.index);
+    }
+    assert_eq!(model.warmup, state.warmup);
+    assert_eq!(model.cooldown, state.cooldown);
+    assert_eq!(model.tgt_raid, state.tgt_raid);
+    assert_eq!(model.supply, state.supply);
+    assert_eq!(model.ttl_buried, state.ttl_buried);
+    assert_eq!(model.ttl_queued, state.ttl_queued);
+    assert_eq!(model.last_vyg, state.last_vyg);
+    assert_eq!(model.paused, state.paused);
+
+    let voyage = setup.sea_client.get_voyage(&setup.voyage_id);
+    let model_voyage = model.voyages.get(setup.voyage_id).unwrap().unwrap();
+    assert_eq!(model_voyage.n_embarked, voyage.n_embarked);
+    assert_eq!(model_voyage.expiration, voyage.expiration);
+
+    for user in [setup.user1_id.clone(), setup.user2_id.clone()] {
+        let user_state = setup.sea_client.user_state(&user);
+        assert_eq!(model.buried_of(user.clone()), user_state.buried);
+        assert_eq!(model.queued_of(user.clone()), user_state.queued);
+        assert!(model.locks_of(user.clone()) == setup.sea_client.get_locks(&user));
+        assert_eq!(
+            model.voyages_of(user.clone(), setup.voyage_id),
+            setup.sea_client.get_u_vygs(&user, &setup.voyage_id)
+        );
+        for role in [
+            Role::VygMaster,
+            Role::Treasurer,
+            Role::RateKeeper,
+            Role::Guardian,
+        ] {
+            assert_eq!(
+                model.has_role(role.clone(), user.clone()),
+                setup.sea_client.has_role(&role, &user)
+            );
+        }
+    }
+    model
+}
+
+#[test]
+fn test_index_buried() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    assert_model_matches(&e, &setup);
+
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+    user2.bury(&BigInt::from_i64(&e, SCALER / 2));
+    user2.bury_lock(&BigInt::from_i64(&e, SCALER / 4), &1);
+    user2.xfer(
+        &Signature::Invoker,
+        &BigInt::zero(&e),
+        &setup.user1_id,
+        &BigInt::from_i64(&e, SCALER / 10),
+    );
+    assert_model_matches(&e, &setup);
+
+    // roles and pauses are picked up by the model
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.grant_role(&Role::Guardian, &setup.user2_id);
+    user2.pause();
+    assert!(assert_model_matches(&e, &setup).paused);
+    user2.unpause();
+    captain.rvke_role(&Role::Guardian, &setup.user2_id);
+    assert_model_matches(&e, &setup);
+
+    // epochs pass, the unearthed doubloons wait out the cooldown while the lock expires
+    captain.set_cooldn(&100);
+    captain.set_rfv(
+        &setup.usdc_token_contract_id,
+        &BigInt::from_i64(&e, 1000 * SCALER),
+    );
+    captain.set_rate(&BigInt::from_i64(&e, 50000));
+    set_sequence(&e, 1210);
+    user2.req_unrth(&BigInt::from_i64(&e, SCALER / 10));
+    assert_model_matches(&e, &setup);
+
+    set_sequence(&e, 910 + 100800);
+    // a week of epochs is more than one call settles, so the index is caught up before unlocking
+    while user2.rebase() > 0 {}
+    user2.cmp_unrth();
+    user2.unlock(&0);
+    let model = assert_model_matches(&e, &setup);
+    assert_eq!(model.locks_of(setup.user2_id.clone()).len(), 0);
+}
+
+#[test]
+fn test_index_raids() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    let oracle_client = setup_oracle(&e, &setup);
+    let secret = BytesN::from_array(&e, &[5; 32]);
+    let hash = e.compute_hash_sha256(&secret.clone().into());
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+    let request_id = user2.commit_rd(&setup.voyage_id, &setup.user1_id, &hash);
+
+    let model = assert_model_matches(&e, &setup);
+    let commitment = setup.sea_client.get_rd_cmt(&request_id);
+    assert_eq!(model.raids.len(), 1);
+    assert!(model.raids[0].raider == commitment.raider);
+    assert!(model.raids[0].voyager == commitment.voyager);
+    assert_eq!(model.raids[0].n_vygs, commitment.n_vygs);
+    assert_eq!(model.raids[0].odds, commitment.odds);
+    assert_eq!(model.raids[0].request_id, Some(request_id));
+
+    // 0x..8b mixed with the secret rolls a successful raid
+    oracle_client.fulfill(&setup.sea_contract_id, &request_id, &randomness(&e, 0x8b));
+    assert!(assert_model_matches(&e, &setup).raids[0].drawn);
+    set_sequence(&e, 911);
+    user2.reveal_rd(&request_id, &secret);
+    let model = assert_model_matches(&e, &setup);
+    assert_eq!(model.raids.len(), 0);
+    assert_eq!(
+        model.voyages_of(setup.user1_id.clone(), setup.voyage_id),
+        BigInt::zero(&e)
+    );
+    // the plunder was minted and the raid cost burned
+    assert_eq!(model.supply, BigInt::from_i64(&e, 99 * SCALER));
+
+    // the captain's handover is picked up by the model
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .prop_capn(&setup.user1_id);
+    let model = assert_model_matches(&e, &setup);
+    assert!(model.nominee == Some(setup.sea_client.capn_prop().nominee));
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .acpt_capn();
+    let model = assert_model_matches(&e, &setup);
+    assert!(model.captain == Some(setup.user1_id.clone()));
+    assert!(model.nominee.is_none());
+}
+
+#[test]
+fn test_index_council() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_cncl(
+            &vec![&e, setup.user1_id.clone(), setup.user2_id.clone()],
+            &2,
+        );
+    let user1 = setup.sea_client.with_source_account(&setup.user1_acct);
+    let action_id = user1.prop_act(&AdminAction::SetWarmup(50));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .aprv_act(&action_id);
+
+    let model = assert_model_matches(&e, &setup);
+    let council = setup.sea_client.council();
+    assert_eq!(model.council.unwrap().members, council.members);
+    assert_eq!(
+        model.actions.get(action_id).unwrap().unwrap().approvals,
+        setup.sea_client.get_action(&action_id).approvals
+    );
+
+    user1.exec_act(&action_id);
+    let model = assert_model_matches(&e, &setup);
+    assert_eq!(model.actions.len(), 0);
+    assert_eq!(model.warmup, 50);
+}
@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{back_rewards, set_sequence, setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+
+// backs the rebase, makes each rebase epoch a week long and raises the rate to 0.5% per epoch
+fn setup_weekly_epochs(e: &Env, setup: &VoyageSetup) {
+    back_rewards(&e, &setup);
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.set_epoch(&100800);
+    captain.set_rate(&BigInt::from_i64(&e, 50000));
+}
+
+#[test]
+fn test_lock_boosts_rewards() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_weekly_epochs(&e, &setup);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+
+    // half of user2's doubloons are buried normally, the other half are locked for a year
+    user2.bury(&BigInt::from_i64(&e, SCALER / 2));
+    let unlock = user2.bury_lock(&BigInt::from_i64(&e, SCALER / 2), &52);
+    assert_eq!(unlock, 910 + 52 * 100800);
+    let lock = setup
+        .sea_client
+        .get_locks(&setup.user2_id)
+        .get(0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(lock.amount, BigInt::from_i64(&e, SCALER / 2));
+    assert_eq!(lock.shares, BigInt::from_i64(&e, 9999955));
+    assert_eq!(lock.boost, BigInt::from_i64(&e, 2 * SCALER));
+    assert_eq!(
+        setup.sea_client.boost_of(&setup.user2_id),
+        BigInt::from_i64(&e, 15000001)
+    );
+
+    // the locked doubloons earn twice the rewards of the buried ones
+    set_sequence(&e, unlock);
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 6480450)
+    );
+    let unlocked = user2.unlock(&0);
+    assert_eq!(unlocked, BigInt::from_i64(&e, 7960901));
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::from_i64(&e, 11142192)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 14441350)
+    );
+    assert_eq!(setup.sea_client.get_locks(&setup.user2_id).len(), 0);
+    assert_eq!(
+        setup.sea_client.boost_of(&setup.user2_id),
+        BigInt::from_i64(&e, SCALER)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(43)")]
+fn test_unlock_early() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_weekly_epochs(&e, &setup);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+
+    let unlock = user2.bury_lock(&BigInt::from_i64(&e, SCALER), &4);
+    set_sequence(&e, unlock - 1);
+    user2.unlock(&0);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(42)")]
+fn test_unearth_locked() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+
+    user2.bury_lock(&BigInt::from_i64(&e, SCALER), &1);
+    user2.unearth(&BigInt::from_i64(&e, 1));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(48)")]
+fn test_lock_shorter_than_warmup() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_warmup(&(100800 + 1));
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury_lock(&BigInt::from_i64(&e, SCALER), &1);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(16)")]
+fn test_invalid_lock_duration() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury_lock(&BigInt::from_i64(&e, SCALER), &2);
+}
@@ -0,0 +1,148 @@
+#![cfg(test)]
+
+use soroban_bag::seven_seas::REQUEST_WINDOW;
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{randomness, set_sequence, setup_oracle, setup_voyage, SCALER};
+extern crate std;
+
+#[test]
+fn test_oracle_raid_success() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
+
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .request_rd(&setup.voyage_id, &setup.user1_id);
+    assert_eq!(request_id, 1);
+    // raid cost is burned when the randomness is requested
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+    assert_eq!(setup.sea_client.get_rd_req(&request_id).odds, 5055);
+
+    // 0x..91 expands to 5689456, below the 21706170 needed for a successful raid
+    oracle_client.fulfill(&setup.sea_contract_id, &request_id, &randomness(&e, 0x91));
+
+    let expected_plunder = setup.user_num_voyages.clone() * BigInt::from_i64(&e, SCALER);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        expected_plunder
+    );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        BigInt::zero(&e)
+    );
+}
+
+#[test]
+fn test_oracle_raid_failure() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
+
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .request_rd(&setup.voyage_id, &setup.user1_id);
+    // 0x..01 expands to 1064052516, well above the 21706170 needed for a successful raid
+    oracle_client.fulfill(&setup.sea_contract_id, &request_id, &randomness(&e, 0x01));
+
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        setup.user_num_voyages
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(3)")]
+fn test_oracle_fulfill_not_oracle() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .request_rd(&setup.voyage_id, &setup.user1_id);
+    // the raider can't settle their own raid
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .fulfill_rd(&request_id, &randomness(&e, 0x91));
+}
+
+#[test]
+fn test_oracle_replaced_refunds_request() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .request_rd(&setup.voyage_id, &setup.user1_id);
+
+    // the old oracle can no longer answer, so once the request window passes the raid is refunded
+    setup_oracle(&e, &setup);
+    set_sequence(&e, 910 + REQUEST_WINDOW + 1);
+    setup.sea_client.expire_rd(&request_id);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, SCALER)
+    );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        setup.user_num_voyages
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(67)")]
+fn test_oracle_request_not_expired() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .request_rd(&setup.voyage_id, &setup.user1_id);
+    set_sequence(&e, 910 + REQUEST_WINDOW);
+    setup.sea_client.expire_rd(&request_id);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(61)")]
+fn test_oracle_fulfill_without_oracle() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .fulfill_rd(&1, &randomness(&e, 0x91));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(63)")]
+fn test_oracle_unknown_request() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    setup.sea_client.get_rd_req(&1);
+}
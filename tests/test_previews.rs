@@ -0,0 +1,111 @@
+#![cfg(test)]
+
+use soroban_sdk::{BigInt, BytesN, Env};
+mod helper;
+use helper::{back_rewards, set_sequence, setup_oracle, setup_voyage, SCALER};
+extern crate std;
+
+#[test]
+fn test_preview_bury_and_unearth() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+    back_rewards(&e, &setup);
+
+    // an epoch is pending, the preview projects the index the bury will settle to
+    set_sequence(&e, 1010);
+    let shares = setup.sea_client.prv_bury(&BigInt::from_i64(&e, SCALER));
+    user2.bury(&BigInt::from_i64(&e, SCALER));
+    assert_eq!(setup.sea_client.get_buried(&setup.user2_id), shares);
+
+    set_sequence(&e, 1350);
+    let doubloons = setup.sea_client.prv_unrth(&shares);
+    user2.unearth(&shares);
+    assert_eq!(setup.base_token_client.balance(&setup.user2_id), doubloons);
+    assert!(doubloons > BigInt::from_i64(&e, SCALER));
+}
+
+#[test]
+fn test_preview_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    assert_eq!(
+        setup
+            .sea_client
+            .prv_vyg(&setup.voyage_id, &BigInt::from_i64(&e, 10)),
+        BigInt::from_i64(&e, 100)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(22)")]
+fn test_preview_voyage_over_capacity() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .prv_vyg(&setup.voyage_id, &BigInt::from_i64(&e, 901));
+}
+
+#[test]
+fn test_preview_end_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_exit_p(&BigInt::from_i64(&e, SCALER / 2));
+
+    // half of the 100 doubloon payout is lost to the early exit penalty
+    let payout = setup
+        .sea_client
+        .prv_end_v(&setup.user1_id, &setup.voyage_id);
+    assert_eq!(payout, BigInt::from_i64(&e, 50 * SCALER));
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&setup.voyage_id);
+    assert_eq!(setup.base_token_client.balance(&setup.user1_id), payout);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(24)")]
+fn test_preview_end_voyage_not_completed() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .prv_end_v(&setup.user1_id, &setup.voyage_id);
+}
+
+#[test]
+fn test_raid_odds() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    let quote = setup
+        .sea_client
+        .raid_odds(&setup.voyage_id, &setup.user1_id);
+    assert_eq!(quote.n_vygs, BigInt::from_i64(&e, 100));
+    assert_eq!(quote.cost, BigInt::from_i64(&e, SCALER));
+    assert_eq!(quote.odds, 5055);
+
+    // committing locks in the quoted odds and burns the quoted cost
+    setup_oracle(&e, &setup);
+    let secret = BytesN::from_array(&e, &[7; 32]);
+    let hash = e.compute_hash_sha256(&secret.into());
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .commit_rd(&setup.voyage_id, &setup.user1_id, &hash);
+    let commitment = setup.sea_client.get_rd_cmt(&request_id);
+    assert_eq!(commitment.n_vygs, quote.n_vygs);
+    assert_eq!(commitment.odds, quote.odds);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+}
@@ -0,0 +1,148 @@
+#![cfg(test)]
+
+use soroban_bag::pricing::{debt_ratio, decay_debt, exp_decay, linear_decay, price};
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::SCALER;
+extern crate std;
+
+#[test]
+fn test_decay_debt() {
+    let e = Env::default();
+    let debt = BigInt::from_i64(&e, 1000 * SCALER);
+
+    assert_eq!(decay_debt(&e, debt.clone(), 0, 100), debt);
+    assert_eq!(
+        decay_debt(&e, debt.clone(), 25, 100),
+        BigInt::from_i64(&e, 750 * SCALER)
+    );
+    assert_eq!(
+        decay_debt(&e, debt.clone(), 99, 100),
+        BigInt::from_i64(&e, 10 * SCALER)
+    );
+    // debt is fully decayed at and after the decay length
+    assert_eq!(decay_debt(&e, debt.clone(), 100, 100), BigInt::zero(&e));
+    assert_eq!(decay_debt(&e, debt, 250, 100), BigInt::zero(&e));
+}
+
+#[test]
+fn test_decay_debt_rounds_in_favor_of_debt() {
+    let e = Env::default();
+
+    // 10 * 1 / 3 = 3.33 decays 3, leaving 7
+    assert_eq!(
+        decay_debt(&e, BigInt::from_i64(&e, 10), 1, 3),
+        BigInt::from_i64(&e, 7)
+    );
+}
+
+#[test]
+fn test_debt_ratio() {
+    let e = Env::default();
+
+    assert_eq!(
+        debt_ratio(
+            &e,
+            BigInt::from_i64(&e, 50 * SCALER),
+            BigInt::from_i64(&e, 1000 * SCALER)
+        ),
+        BigInt::from_i64(&e, SCALER / 20)
+    );
+    assert_eq!(
+        debt_ratio(
+            &e,
+            BigInt::from_i64(&e, 3000 * SCALER),
+            BigInt::from_i64(&e, 1000 * SCALER)
+        ),
+        BigInt::from_i64(&e, 3 * SCALER)
+    );
+    // debt with no supply counts as 100%, and no debt is no ratio
+    assert_eq!(
+        debt_ratio(&e, BigInt::from_i64(&e, 50 * SCALER), BigInt::zero(&e)),
+        BigInt::from_i64(&e, SCALER)
+    );
+    assert_eq!(
+        debt_ratio(
+            &e,
+            BigInt::from_i64(&e, 50 * SCALER),
+            BigInt::from_i64(&e, -1)
+        ),
+        BigInt::from_i64(&e, SCALER)
+    );
+    assert_eq!(
+        debt_ratio(&e, BigInt::zero(&e), BigInt::zero(&e)),
+        BigInt::zero(&e)
+    );
+}
+
+#[test]
+fn test_price() {
+    let e = Env::default();
+    let floor = BigInt::from_i64(&e, 5);
+
+    // 200 * 5% = 10
+    assert_eq!(
+        price(
+            &e,
+            BigInt::from_i64(&e, 200),
+            BigInt::from_i64(&e, SCALER / 20),
+            floor.clone()
+        ),
+        BigInt::from_i64(&e, 10)
+    );
+    // 200 * 1% = 2 is below the floor
+    assert_eq!(
+        price(
+            &e,
+            BigInt::from_i64(&e, 200),
+            BigInt::from_i64(&e, SCALER / 100),
+            floor.clone()
+        ),
+        floor
+    );
+}
+
+#[test]
+fn test_linear_decay() {
+    let e = Env::default();
+    let start = BigInt::from_i64(&e, 100);
+    let reserve = BigInt::from_i64(&e, 20);
+
+    assert_eq!(
+        linear_decay(&e, start.clone(), reserve.clone(), 0, 100),
+        start
+    );
+    assert_eq!(
+        linear_decay(&e, start.clone(), reserve.clone(), 25, 100),
+        BigInt::from_i64(&e, 80)
+    );
+    assert_eq!(
+        linear_decay(&e, start.clone(), reserve.clone(), 100, 100),
+        reserve
+    );
+    assert_eq!(linear_decay(&e, start, reserve.clone(), 150, 100), reserve);
+}
+
+#[test]
+fn test_exp_decay() {
+    let e = Env::default();
+    let start = BigInt::from_i64(&e, 100);
+    let reserve = BigInt::from_i64(&e, 20);
+
+    assert_eq!(exp_decay(&e, start.clone(), reserve.clone(), 0, 10), start);
+    // half way through the first half life, a quarter of the premium is gone
+    assert_eq!(
+        exp_decay(&e, start.clone(), reserve.clone(), 5, 10),
+        BigInt::from_i64(&e, 80)
+    );
+    assert_eq!(
+        exp_decay(&e, start.clone(), reserve.clone(), 10, 10),
+        BigInt::from_i64(&e, 60)
+    );
+    assert_eq!(
+        exp_decay(&e, start.clone(), reserve.clone(), 20, 10),
+        BigInt::from_i64(&e, 40)
+    );
+    // the premium runs out after enough half lives
+    assert_eq!(exp_decay(&e, start, reserve.clone(), 1000, 10), reserve);
+}
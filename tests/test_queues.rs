@@ -0,0 +1,169 @@
+#![cfg(test)]
+
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{set_sequence, setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+
+// raises the rate to 0.5% per epoch and sets a warmup and cooldown of one epoch
+fn setup_queues(e: &Env, setup: &VoyageSetup, warmup: u32, cooldown: u32) {
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.set_rate(&BigInt::from_i64(&e, 50000));
+    captain.set_warmup(&warmup);
+    captain.set_cooldn(&cooldown);
+}
+
+#[test]
+fn test_warmup() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_queues(&e, &setup, 100, 0);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rfv(
+            &setup.usdc_token_contract_id,
+            &BigInt::from_i64(&e, 10 * SCALER),
+        );
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+
+    // the doubloons are taken but wait in the warmup queue instead of being buried
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+    let queue = setup.sea_client.get_wrmp(&setup.user2_id);
+    assert_eq!(queue.len(), 1);
+    let entry = queue.get(0).unwrap().unwrap();
+    assert_eq!(entry.amount, BigInt::from_i64(&e, SCALER));
+    assert_eq!(entry.ready, 1010);
+    // queued doubloons are still owed by the sea
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 10000));
+
+    // the doubloons earned nothing during the warmup, so they buy fewer shares at the new index
+    set_sequence(&e, 1010);
+    let claimed = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .claim_wrmp();
+    assert_eq!(claimed, BigInt::from_i64(&e, SCALER));
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::from_i64(&e, 9950203)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 9999999)
+    );
+    assert_eq!(setup.sea_client.get_wrmp(&setup.user2_id).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(46)")]
+fn test_claim_warmup_early() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_queues(&e, &setup, 100, 0);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+    set_sequence(&e, 1009);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .claim_wrmp();
+}
+
+#[test]
+fn test_cooldown() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_queues(&e, &setup, 0, 100);
+
+    // without a warmup doubloons are buried immediately
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+    let shares = setup.sea_client.get_buried(&setup.user2_id);
+    assert_eq!(shares, BigInt::from_i64(&e, 9999955));
+
+    let ready = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .req_unrth(&shares);
+    assert_eq!(ready, 1010);
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+    let entry = setup
+        .sea_client
+        .get_cool(&setup.user2_id)
+        .get(0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.amount, BigInt::from_i64(&e, 9999999));
+
+    // the unearthed doubloons stop earning when they're requested
+    set_sequence(&e, 1110);
+    let minted = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .cmp_unrth();
+    assert_eq!(minted, BigInt::from_i64(&e, 9999999));
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 9999999)
+    );
+    assert_eq!(setup.sea_client.get_cool(&setup.user2_id).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(46)")]
+fn test_complete_unearth_early() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_queues(&e, &setup, 0, 100);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .req_unrth(&BigInt::from_i64(&e, 9999955));
+    set_sequence(&e, 1009);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .cmp_unrth();
+}
+
+#[test]
+#[should_panic(expected = "ContractError(45)")]
+fn test_unearth_with_cooldown() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_queues(&e, &setup, 0, 100);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .unearth(&BigInt::from_i64(&e, 9999955));
+}
@@ -1,108 +1,221 @@
 #![cfg(test)]
 
-use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{
-    testutils::{Accounts, Ledger, LedgerInfo},
-    BigInt, Env,
-};
+use soroban_bag::seven_seas::{REQUEST_WINDOW, REVEAL_WINDOW};
+use soroban_sdk::{BigInt, BytesN, Env};
 mod helper;
 use helper::{
-    create_base_token_contract, create_sea_contract, create_usdc_token_contract,
-    generate_contract_id,
+    randomness, set_sequence, setup_oracle, setup_voyage, MockOracleClient, VoyageSetup, SCALER,
 };
 extern crate std;
 
+// commits to a raid on user1 with user2 and returns the request id and the secret
+fn commit(e: &Env, setup: &VoyageSetup) -> (u32, BytesN<32>) {
+    let secret = BytesN::from_array(e, &[5; 32]);
+    let hash = e.compute_hash_sha256(&secret.clone().into());
+    let request_id = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .commit_rd(&setup.voyage_id, &setup.user1_id, &hash);
+    (request_id, secret)
+}
+
+// has the oracle provide randomness for a commitment
+fn draw(
+    setup: &VoyageSetup,
+    oracle_client: &MockOracleClient,
+    request_id: u32,
+    beacon: BytesN<32>,
+) {
+    oracle_client.fulfill(&setup.sea_contract_id, &request_id, &beacon);
+}
+
 #[test]
-#[should_panic(expected = "Not Implemented")]
-fn test_raid_happy_path() {
+fn test_raid_success() {
     let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
 
-    //set ledger sequence so we can estimate voyage expiration
-    e.ledger().set(LedgerInfo {
-        timestamp: 100,
-        protocol_version: 1,
-        sequence_number: 10,
-        network_passphrase: Default::default(),
-        base_reserve: 10,
-    });
-
-    let max_vygs = BigInt::from_i64(&e, 1000);
-    let vyg_price = BigInt::from_i64(&e, 10);
-    let user_num_voyages = BigInt::from_i64(&e, 100);
-    let user_usdc_spend = vyg_price.clone() * user_num_voyages.clone();
-
-    // deploy token contracts
-    let token_admin = e.accounts().generate_and_create();
-    let usdc_token_contract_id = generate_contract_id(&e);
-    let base_token_contract_id = generate_contract_id(&e);
-    let usdc_token_client = create_usdc_token_contract(&e, &usdc_token_contract_id, &token_admin);
-    let base_token_client = create_base_token_contract(&e, &base_token_contract_id, &token_admin);
-
-    // setup env
-    let user1_acct = e.accounts().generate_and_create();
-    let user1_id = Identifier::Account(user1_acct.clone());
-    usdc_token_client.with_source_account(&token_admin).mint(
-        &soroban_auth::Signature::Invoker,
-        &BigInt::zero(&e),
-        &user1_id,
-        &user_usdc_spend,
+    let (request_id, secret) = commit(&e, &setup);
+    // raid cost is burned and the odds are locked in at commit time
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
     );
-    let user2_acct = e.accounts().generate_and_create();
-    let user2_id = Identifier::Account(user2_acct.clone());
-    base_token_client.with_source_account(&token_admin).mint(
-        &soroban_auth::Signature::Invoker,
-        &BigInt::zero(&e),
-        &user2_id,
-        &BigInt::from_i64(&e, 1),
+    assert_eq!(setup.sea_client.get_l_raid(), 910);
+    assert_eq!(setup.sea_client.get_rd_cmt(&request_id).odds, 5055);
+
+    // 0x..8b mixed with the secret rolls 593365, below the 21706170 needed for a successful raid
+    draw(&setup, &oracle_client, request_id, randomness(&e, 0x8b));
+    set_sequence(&e, 911);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .reveal_rd(&request_id, &secret);
+
+    // raider received the voyager's doubloons
+    let expected_plunder = setup.user_num_voyages.clone() * BigInt::from_i64(&e, SCALER);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        expected_plunder
     );
-    assert_eq!(usdc_token_client.balance(&user1_id), user_usdc_spend);
-    // deploy and init sea
-    let rate = BigInt::from_i64(&e, 5);
-    let target_raid_interval: u32 = 1800;
-    let sea_contract_id = generate_contract_id(&e);
-    let sea_id = Identifier::Contract(sea_contract_id.clone());
-    let sea_client = create_sea_contract(&e, &sea_contract_id);
-    sea_client.with_source_account(&token_admin).initialize(
-        &base_token_contract_id,
-        &rate,
-        &target_raid_interval,
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        BigInt::zero(&e)
     );
+}
+
+#[test]
+fn test_raid_failure() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
 
-    // transfer admin priviliges
-    base_token_client
-        .with_source_account(&token_admin)
-        .set_admin(&Signature::Invoker, &BigInt::zero(&e), &sea_id);
+    // 0x..01 mixed with the secret rolls 1970091075, well above the 21706170 needed for a successful raid
+    let (request_id, secret) = commit(&e, &setup);
+    draw(&setup, &oracle_client, request_id, randomness(&e, 0x01));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .reveal_rd(&request_id, &secret);
 
-    // create voyage
-    sea_client.with_source_account(&token_admin).new_voyage(
-        &usdc_token_contract_id,
-        &vyg_price,
-        &max_vygs,
+    // raider lost the raid cost and the voyager kept their voyages
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
     );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        setup.user_num_voyages
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(61)")]
+fn test_raid_needs_oracle() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // without an oracle there is no randomness the raider can't grind
+    commit(&e, &setup);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(28)")]
+fn test_raid_no_voyages() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    let hash = BytesN::from_array(&e, &[0; 32]);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .commit_rd(&setup.voyage_id, &setup.user2_id, &hash);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(64)")]
+fn test_raid_wrong_secret() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
+
+    let (request_id, _) = commit(&e, &setup);
+    draw(&setup, &oracle_client, request_id, randomness(&e, 0x8b));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .reveal_rd(&request_id, &BytesN::from_array(&e, &[8; 32]));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(65)")]
+fn test_raid_reveal_before_randomness() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    let (request_id, secret) = commit(&e, &setup);
+    set_sequence(&e, 911);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .reveal_rd(&request_id, &secret);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(66)")]
+fn test_raid_reveal_expired() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
+
+    let (request_id, secret) = commit(&e, &setup);
+    draw(&setup, &oracle_client, request_id, randomness(&e, 0x8b));
+    set_sequence(&e, 910 + REVEAL_WINDOW + 1);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .reveal_rd(&request_id, &secret);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(67)")]
+fn test_raid_expire_before_randomness() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    // the oracle still has time to answer, and the reveal window only starts once it does
+    let (request_id, _) = commit(&e, &setup);
+    set_sequence(&e, 910 + REQUEST_WINDOW);
+    setup.sea_client.expire_rd(&request_id);
+}
+
+#[test]
+fn test_raid_expire_refunds_unanswered() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_oracle(&e, &setup);
+
+    // the oracle never answers, so the raid is called off and the raider gets their cost back
+    let (request_id, _) = commit(&e, &setup);
+    set_sequence(&e, 910 + REQUEST_WINDOW + 1);
+    setup.sea_client.expire_rd(&request_id);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, SCALER)
+    );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        setup.user_num_voyages
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(63)")]
+fn test_raid_expire_forfeits() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let oracle_client = setup_oracle(&e, &setup);
 
-    // enter voyage with user
-    usdc_token_client.with_source_account(&user1_acct).approve(
-        &Signature::Invoker,
-        &BigInt::zero(&e),
-        &sea_id,
-        &user_usdc_spend,
+    let (request_id, secret) = commit(&e, &setup);
+    draw(&setup, &oracle_client, request_id, randomness(&e, 0x8b));
+    set_sequence(&e, 910 + REVEAL_WINDOW + 1);
+    // anyone can clear the expired commitment, the raid cost stays burned
+    setup.sea_client.expire_rd(&request_id);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
     );
-    let expected_id: i32 = 1;
-    sea_client
-        .with_source_account(&user1_acct)
-        .voyage(&expected_id, &user_num_voyages);
-
-    //let time pass
-    let new_block: u32 = 10 + 900;
-    e.ledger().set(LedgerInfo {
-        timestamp: 100,
-        protocol_version: 1,
-        sequence_number: new_block,
-        network_passphrase: Default::default(),
-        base_reserve: 10,
-    });
-
-    //TODO: add testing for raiding success, we need to wait for the PRNG PR - https://github.com/stellar/rs-soroban-env/pull/544
-    //Currently we just check that the function emits a not implemented panic
-    sea_client.raid(&expected_id, &user1_id);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .reveal_rd(&request_id, &secret);
 }
@@ -0,0 +1,250 @@
+#![cfg(test)]
+
+use soroban_bag::rebase::{compound, pow_fixed, MAX_EPOCHS};
+use soroban_sdk::{symbol, testutils::Events, vec, BigInt, Env, IntoVal};
+mod helper;
+use helper::{back_rewards, set_sequence, setup_voyage, SCALER};
+extern crate std;
+
+const ONE: i64 = SCALER * SCALER;
+
+// checks that a power is never above the exact value and within the documented rounding bound of it
+fn assert_within_bound(e: &Env, result: BigInt, exact: i64, epochs: u32) {
+    let exact = BigInt::from_i64(&e, exact);
+    let bound = exact.clone() * BigInt::from_u32(&e, epochs + 1) / BigInt::from_i64(&e, ONE)
+        + BigInt::from_i64(&e, 1);
+    assert!(result <= exact);
+    assert!(exact - result <= bound);
+}
+
+// repeated fixed point multiplication, one epoch at a time
+fn reference_pow(e: &Env, base: BigInt, exp: u32) -> BigInt {
+    let one = BigInt::from_i64(&e, ONE);
+    let mut result = one.clone();
+    for _ in 0..exp {
+        result = result * base.clone() / one.clone();
+    }
+    result
+}
+
+#[test]
+fn test_pow_fixed_edge_cases() {
+    let e = Env::default();
+    let growth = BigInt::from_i64(&e, ONE + 500 * SCALER);
+
+    assert_eq!(pow_fixed(&e, growth.clone(), 0), BigInt::from_i64(&e, ONE));
+    assert_eq!(pow_fixed(&e, growth.clone(), 1), growth);
+    // a rate of zero never grows
+    assert_eq!(
+        pow_fixed(&e, BigInt::from_i64(&e, ONE), 1000000),
+        BigInt::from_i64(&e, ONE)
+    );
+}
+
+#[test]
+fn test_pow_fixed_against_exact() {
+    let e = Env::default();
+
+    // exact values are floor(10^14 * (1 + rate / 10^7)^epochs), computed with arbitrary precision
+    let growth = BigInt::from_i64(&e, ONE + 500 * SCALER);
+    let result = pow_fixed(&e, growth.clone(), 100);
+    assert_eq!(result, BigInt::from_i64(&e, 100501239523700));
+    assert_within_bound(&e, result, 100501239523703, 100);
+
+    let result = pow_fixed(&e, growth, 1000);
+    assert_eq!(result, BigInt::from_i64(&e, 105126978233150));
+    assert_within_bound(&e, result, 105126978233177, 1000);
+
+    // a long gap of a million epochs is still within the bound
+    let growth = BigInt::from_i64(&e, ONE + 5 * SCALER);
+    let result = pow_fixed(&e, growth, 1000000);
+    assert_eq!(result, BigInt::from_i64(&e, 164872106439461));
+    assert_within_bound(&e, result, 164872106461005, 1000000);
+}
+
+#[test]
+fn test_pow_fixed_against_reference() {
+    let e = Env::default();
+    let growth = BigInt::from_i64(&e, ONE + 500 * SCALER);
+
+    for epochs in [2, 3, 7, 64, 100, 255, 1000] {
+        let expected = reference_pow(&e, growth.clone(), epochs);
+        let result = pow_fixed(&e, growth.clone(), epochs);
+        // both truncate, so they agree to within the rounding bound of each other
+        let bound = expected.clone() * BigInt::from_u32(&e, epochs + 1) / BigInt::from_i64(&e, ONE)
+            + BigInt::from_i64(&e, 1);
+        let diff = if result > expected {
+            result - expected
+        } else {
+            expected - result
+        };
+        assert!(diff <= bound);
+    }
+}
+
+#[test]
+fn test_compound_is_path_independent() {
+    let e = Env::default();
+    let index = BigInt::from_i64(&e, ONE);
+    let rate = BigInt::from_i64(&e, 500);
+
+    // compounding 100 epochs at once matches compounding them in two updates, up to rounding
+    let once = compound(&e, index.clone(), rate.clone(), 100);
+    let twice = compound(&e, compound(&e, index.clone(), rate.clone(), 37), rate, 63);
+    let diff = if once > twice {
+        once.clone() - twice
+    } else {
+        twice - once.clone()
+    };
+    assert!(diff <= BigInt::from_i64(&e, 200));
+    assert_eq!(
+        compound(&e, index.clone(), BigInt::from_i64(&e, 500), 0),
+        index
+    );
+}
+
+#[test]
+fn test_rebase_settles_pending_epochs() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    back_rewards(&e, &setup);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rate(&BigInt::from_i64(&e, 50000));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+
+    // the 9 epochs before the reserves were valued were settled by `set_rfv`
+    let epoch = setup.sea_client.epoch();
+    assert_eq!(epoch.number, 9);
+    assert_eq!(epoch.length, 100);
+    assert_eq!(epoch.next_ldgr, 1010);
+    assert_eq!(epoch.pending, 0);
+
+    set_sequence(&e, 1150);
+    assert_eq!(setup.sea_client.epoch().pending, 2);
+    let settled = setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .rebase();
+    assert_eq!(settled, 2);
+
+    // an event per epoch with the doubloons distributed in it and the index after it
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 2..),
+        vec![
+            &e,
+            (
+                setup.sea_contract_id.clone(),
+                (symbol!("rebase"), 10_u32).into_val(&e),
+                (
+                    BigInt::from_i64(&e, 49999),
+                    BigInt::from_i64(&e, 100500452250904)
+                )
+                    .into_val(&e)
+            ),
+            (
+                setup.sea_contract_id.clone(),
+                (symbol!("rebase"), 11_u32).into_val(&e),
+                (
+                    BigInt::from_i64(&e, 50250),
+                    BigInt::from_i64(&e, 101002954512159)
+                )
+                    .into_val(&e)
+            )
+        ]
+    );
+    let epoch = setup.sea_client.epoch();
+    assert_eq!(epoch.number, 11);
+    assert_eq!(epoch.next_ldgr, 1210);
+    assert_eq!(
+        setup.sea_client.get_index(),
+        BigInt::from_i64(&e, 101002954512159)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10100249)
+    );
+
+    // the caller is paid the bounty, but only when epochs are settled
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_bounty(&BigInt::from_i64(&e, 100));
+    set_sequence(&e, 1250);
+    let settled = setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .rebase();
+    assert_eq!(settled, 1);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user1_id),
+        BigInt::from_i64(&e, 100)
+    );
+    let settled = setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .rebase();
+    assert_eq!(settled, 0);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user1_id),
+        BigInt::from_i64(&e, 100)
+    );
+}
+
+#[test]
+fn test_set_epoch() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // the 9 pending epochs are settled at the old length before it changes
+    set_sequence(&e, 950);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_epoch(&10);
+    let epoch = setup.sea_client.epoch();
+    assert_eq!(epoch.number, 9);
+    assert_eq!(epoch.length, 10);
+    assert_eq!(epoch.next_ldgr, 920);
+    assert_eq!(epoch.pending, 4);
+}
+
+#[test]
+fn test_rebase_clears_backlog() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // one call settles at most MAX_EPOCHS epochs, the rest wait for the next call
+    set_sequence(&e, 910 + 100 * (MAX_EPOCHS + 5));
+    let index = setup.sea_client.get_index();
+    assert_eq!(setup.sea_client.rebase(), MAX_EPOCHS);
+    assert_eq!(setup.sea_client.epoch().pending, 14);
+    assert_eq!(setup.sea_client.rebase(), 14);
+    assert_eq!(setup.sea_client.epoch().number, MAX_EPOCHS + 14);
+    // settling in two calls ends on the index that was projected before either
+    let diff = if index > setup.sea_client.get_index() {
+        index - setup.sea_client.get_index()
+    } else {
+        setup.sea_client.get_index() - index
+    };
+    assert!(diff <= BigInt::from_i64(&e, 1));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(18)")]
+fn test_bury_refused_with_backlog() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // burying now would change what the unsettled epochs pay
+    set_sequence(&e, 910 + 100 * (MAX_EPOCHS + 5));
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+}
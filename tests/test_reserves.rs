@@ -0,0 +1,168 @@
+#![cfg(test)]
+
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{set_sequence, setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+
+// values usdc at `value` doubloons, raises the rate to 0.5% per epoch and buries user2's doubloons
+fn setup_reserves(e: &Env, setup: &VoyageSetup, value: i64) {
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.set_rate(&BigInt::from_i64(&e, 50000));
+    captain.set_rfv(
+        &setup.usdc_token_contract_id,
+        &BigInt::from_i64(&e, value * SCALER),
+    );
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+}
+
+#[test]
+fn test_reserves_track_voyages() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // user1 paid 1000 usdc for their voyages, but it has no value until the captain sets one
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::zero(&e));
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rfv(
+            &setup.usdc_token_contract_id,
+            &BigInt::from_i64(&e, 10 * SCALER),
+        );
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 10000));
+
+    // moving funds out of the treasury reduces the reserves
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .xfer_held(
+            &setup.usdc_token_contract_id,
+            &setup.user2_id,
+            &BigInt::from_i64(&e, 400),
+        );
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 6000));
+}
+
+#[test]
+fn test_rewards_capped_by_excess_reserves() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_reserves(&e, &setup, 10);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rwdcap(&BigInt::from_i64(&e, SCALER / 2));
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 10001));
+
+    // the rate would pay ~50000 doubloons an epoch, but only half the excess can be paid each epoch
+    set_sequence(&e, 1110);
+    setup.sea_client.rebase();
+    assert_eq!(
+        setup.sea_client.get_index(),
+        BigInt::from_i64(&e, 100075450338401)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10007499)
+    );
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 2501));
+}
+
+#[test]
+fn test_rewards_capped_after_uncapped_epochs() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_reserves(&e, &setup, 1000);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rwdcap(&BigInt::from_i64(&e, SCALER / 10));
+
+    // the first epochs pay the full rate, the cap binds once a tenth of the excess drops below it
+    set_sequence(&e, 2410);
+    let projected = setup.sea_client.get_index();
+    assert_eq!(projected, BigInt::from_i64(&e, 107115355221993));
+    setup.sea_client.rebase();
+    assert_eq!(setup.sea_client.get_index(), projected);
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10711487)
+    );
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 288513));
+}
+
+#[test]
+fn test_rewards_capped_by_default() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_reserves(&e, &setup, 10);
+
+    // without a reward cap set the first epoch pays out all 10001 doubloons of excess and the second nothing
+    set_sequence(&e, 1110);
+    setup.sea_client.rebase();
+    assert_eq!(
+        setup.sea_client.get_index(),
+        BigInt::from_i64(&e, 100100460450947)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10010000)
+    );
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::zero(&e));
+    assert_eq!(setup.sea_client.runway(), 0);
+}
+
+#[test]
+fn test_runway() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // nothing is buried, so the rate costs nothing
+    assert_eq!(setup.sea_client.runway(), u32::MAX);
+
+    // 1000001 doubloons of excess reserves pay 0.5% on 10 million buried doubloons for 19 epochs
+    setup_reserves(&e, &setup, 1000);
+    assert_eq!(setup.sea_client.excess_rsv(), BigInt::from_i64(&e, 1000001));
+    assert_eq!(setup.sea_client.runway(), 19);
+}
+
+#[test]
+fn test_rate_without_runway() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+
+    // the cap keeps rewards within the excess reserves, so any rate is allowed but nothing backs it
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rate(&BigInt::from_i64(&e, 50000));
+    assert_eq!(setup.sea_client.runway(), 0);
+
+    // the only excess is the one unit lost to rounding when user2 buried
+    set_sequence(&e, 1110);
+    setup.sea_client.rebase();
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10000000)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(10)")]
+fn test_negative_rate() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rate(&BigInt::from_i64(&e, -1));
+}
@@ -0,0 +1,284 @@
+#![cfg(test)]
+
+use soroban_auth::Identifier;
+use soroban_bag::seven_seas::{PriceMode, Role};
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{setup_voyage, VoyageSetup, SCALER};
+extern crate std;
+
+// creates a second voyage offering with the invoker
+fn new_voyage(e: &Env, setup: &VoyageSetup, invoker: &soroban_sdk::AccountId) {
+    setup.sea_client.with_source_account(invoker).new_voyage(
+        &setup.usdc_token_contract_id,
+        &setup.vyg_price,
+        &BigInt::from_i64(e, 1000),
+        &BigInt::from_i64(e, SCALER),
+        &910,
+        &(910 + 100700),
+        &100,
+        &PriceMode::Fixed,
+    );
+}
+
+#[test]
+fn test_initial_roles() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain_id = Identifier::Account(setup.token_admin.clone());
+
+    // the first captain holds every role, everyone else holds none
+    for role in [
+        Role::VygMaster,
+        Role::Treasurer,
+        Role::RateKeeper,
+        Role::Guardian,
+    ] {
+        assert!(setup.sea_client.has_role(&role, &captain_id));
+        assert!(!setup.sea_client.has_role(&role, &setup.user1_id));
+    }
+}
+
+#[test]
+fn test_voyage_master() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .grant_role(&Role::VygMaster, &setup.user1_id);
+
+    new_voyage(&e, &setup, &setup.user1_acct);
+    assert_eq!(setup.sea_client.prot_state().last_vyg, 2);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(8)")]
+fn test_voyage_master_required() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    new_voyage(&e, &setup, &setup.user1_acct);
+}
+
+#[test]
+fn test_treasurer() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .grant_role(&Role::Treasurer, &setup.user2_id);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .xfer_held(
+            &setup.usdc_token_contract_id,
+            &setup.user2_id,
+            &BigInt::from_i64(&e, 400),
+        );
+    assert_eq!(
+        setup.usdc_token_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 400)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(8)")]
+fn test_treasurer_required() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    // holding every other role isn't enough to move the treasury
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.rvke_role(
+        &Role::Treasurer,
+        &Identifier::Account(setup.token_admin.clone()),
+    );
+
+    captain.xfer_held(
+        &setup.usdc_token_contract_id,
+        &setup.user2_id,
+        &BigInt::from_i64(&e, 400),
+    );
+}
+
+#[test]
+fn test_rate_keeper() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .grant_role(&Role::RateKeeper, &setup.user1_id);
+
+    let rate_keeper = setup.sea_client.with_source_account(&setup.user1_acct);
+    rate_keeper.set_rate(&BigInt::from_i64(&e, 50000));
+    rate_keeper.set_tgt_ri(&900);
+    let state = setup.sea_client.prot_state();
+    assert_eq!(state.rate, BigInt::from_i64(&e, 50000));
+    assert_eq!(state.tgt_raid, 900);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(8)")]
+fn test_rate_keeper_required() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    // the captain can't tune raids once their rate keeper role is revoked
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.grant_role(&Role::RateKeeper, &setup.user1_id);
+    captain.rvke_role(
+        &Role::RateKeeper,
+        &Identifier::Account(setup.token_admin.clone()),
+    );
+    assert!(!setup.sea_client.has_role(
+        &Role::RateKeeper,
+        &Identifier::Account(setup.token_admin.clone())
+    ));
+
+    captain.set_tgt_ri(&900);
+}
+
+#[test]
+fn test_guardian() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .grant_role(&Role::Guardian, &setup.user1_id);
+
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+    user2.bury(&BigInt::from_i64(&e, SCALER / 2));
+
+    let guardian = setup.sea_client.with_source_account(&setup.user1_acct);
+    guardian.pause();
+    assert!(setup.sea_client.prot_state().paused);
+    // users can still leave while the sea is paused
+    user2.unearth(&setup.sea_client.get_buried(&setup.user2_id));
+
+    guardian.unpause();
+    assert!(!setup.sea_client.prot_state().paused);
+    user2.bury(&BigInt::from_i64(&e, SCALER / 2));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(8)")]
+fn test_guardian_required() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .pause();
+}
+
+#[test]
+#[should_panic(expected = "ContractError(9)")]
+fn test_paused_bury() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .pause();
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(9)")]
+fn test_paused_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .pause();
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .voyage(&setup.voyage_id, &BigInt::from_i64(&e, 1), &setup.vyg_price);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(1)")]
+fn test_grant_captain_only() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    // holding a role doesn't let you hand it out
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.grant_role(&Role::Guardian, &setup.user1_id);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .grant_role(&Role::Guardian, &setup.user2_id);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(1)")]
+fn test_revoke_captain_only() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .rvke_role(
+            &Role::Guardian,
+            &Identifier::Account(setup.token_admin.clone()),
+        );
+}
+
+#[test]
+fn test_handover_moves_roles() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain_id = Identifier::Account(setup.token_admin.clone());
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.grant_role(&Role::Guardian, &setup.user2_id);
+    captain.prop_capn(&setup.user1_id);
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .acpt_capn();
+
+    // the new captain takes over the old captain's roles, other holders keep theirs
+    for role in [
+        Role::VygMaster,
+        Role::Treasurer,
+        Role::RateKeeper,
+        Role::Guardian,
+    ] {
+        assert!(setup.sea_client.has_role(&role, &setup.user1_id));
+        assert!(!setup.sea_client.has_role(&role, &captain_id));
+    }
+    assert!(setup.sea_client.has_role(&Role::Guardian, &setup.user2_id));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(8)")]
+fn test_renounce_revokes_roles() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.grant_role(&Role::Guardian, &setup.user1_id);
+    captain.rnce_capn();
+    assert!(!setup.sea_client.has_role(
+        &Role::Treasurer,
+        &Identifier::Account(setup.token_admin.clone())
+    ));
+
+    // no one is left to hold a role once the captaincy is given up
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .pause();
+}
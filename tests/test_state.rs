@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{set_sequence, setup_voyage, SCALER};
+extern crate std;
+
+#[test]
+fn test_protocol_state() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    let state = setup.sea_client.prot_state();
+    assert_eq!(
+        state.captain,
+        Identifier::Account(setup.token_admin.clone())
+    );
+    assert_eq!(state.decimals, 7);
+    assert_eq!(state.rate, BigInt::from_i64(&e, 5));
+    // nothing has settled the 9 pending epochs, the index is projected to the current block
+    assert_eq!(state.index, BigInt::from_i64(&e, 100000450000900));
+    assert_eq!(state.index, setup.sea_client.get_index());
+    assert_eq!(state.last_ldgr, 10);
+    assert_eq!(state.epoch.number, 0);
+    assert_eq!(state.epoch.pending, 9);
+    assert_eq!(state.ttl_buried, BigInt::zero(&e));
+    assert_eq!(state.supply, BigInt::zero(&e));
+    assert_eq!(state.exit_pen, BigInt::from_i64(&e, SCALER));
+    assert_eq!(state.tgt_raid, 1800);
+    assert_eq!(state.last_raid, 0);
+    assert_eq!(state.last_vyg, 1);
+}
+
+#[test]
+fn test_user_state() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let user2 = setup.sea_client.with_source_account(&setup.user2_acct);
+    user2.bury(&BigInt::from_i64(&e, SCALER / 2));
+    user2.bury_lock(&BigInt::from_i64(&e, SCALER / 2), &1);
+
+    let state = setup.sea_client.user_state(&setup.user2_id);
+    assert_eq!(state.doubloons, BigInt::zero(&e));
+    assert_eq!(state.buried, BigInt::from_i64(&e, 4999977));
+    assert_eq!(state.value, BigInt::from_i64(&e, 4999999));
+    assert_eq!(state.locked, BigInt::from_i64(&e, 4999999));
+    assert_eq!(state.queued, BigInt::zero(&e));
+    assert_eq!(state.voyages.len(), 0);
+
+    let state = setup.sea_client.user_state(&setup.user1_id);
+    assert_eq!(state.voyages.len(), 1);
+    let status = state.voyages.get(setup.voyage_id).unwrap().unwrap();
+    assert_eq!(status.n_vygs, setup.user_num_voyages);
+    assert_eq!(status.matures, 100811);
+    assert!(!status.mature);
+    assert_eq!(status.payout, BigInt::from_i64(&e, 100 * SCALER));
+
+    // voyages drop out of the state once they have been ended
+    set_sequence(&e, 100811);
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&setup.voyage_id);
+    assert_eq!(
+        setup.sea_client.user_state(&setup.user1_id).voyages.len(),
+        0
+    );
+}
@@ -1,14 +1,15 @@
 #![cfg(test)]
 
 use soroban_auth::{Identifier, Signature};
+use soroban_bag::seven_seas::{DataKey, ExpDecay, PriceMode, VoyageInfoV1, VoyageKey};
 use soroban_sdk::{
     testutils::{Accounts, Ledger, LedgerInfo},
-    BigInt, Env,
+    BigInt, Env, Map,
 };
 mod helper;
 use helper::{
-    create_base_token_contract, create_sea_contract, create_usdc_token_contract,
-    generate_contract_id, SCALER,
+    create_base_token_contract, create_sea_contract, create_usdc_token_contract, embark,
+    generate_contract_id, set_sequence, setup_voyage, VoyageSetup, SCALER,
 };
 extern crate std;
 #[test]
@@ -48,6 +49,11 @@ fn test_create_voyage() {
         &usdc_token_contract_id,
         &vyg_price,
         &max_vygs,
+        &BigInt::from_i64(&e, SCALER),
+        &10,
+        &(10 + 100700),
+        &100,
+        &PriceMode::Fixed,
     );
 
     //check that voyage was created
@@ -59,6 +65,9 @@ fn test_create_voyage() {
     assert_eq!(usdc_voyage.price, vyg_price);
     assert_eq!(usdc_voyage.expiration, expected_expiration);
     assert_eq!(usdc_voyage.n_embarked, BigInt::zero(&e));
+    assert_eq!(usdc_voyage.start_ldgr, 10);
+    assert_eq!(usdc_voyage.embark_dl, 10 + 100700);
+    assert_eq!(usdc_voyage.sail_dur, 100);
 }
 
 #[test]
@@ -113,6 +122,11 @@ fn test_voyage() {
         &usdc_token_contract_id,
         &vyg_price,
         &max_vygs,
+        &BigInt::from_i64(&e, SCALER),
+        &10,
+        &(10 + 100700),
+        &100,
+        &PriceMode::Fixed,
     );
 
     // enter voyage with user
@@ -125,7 +139,7 @@ fn test_voyage() {
     let expected_id: i32 = 1;
     sea_client
         .with_source_account(&user1_acct)
-        .voyage(&expected_id, &user_num_voyages);
+        .voyage(&expected_id, &user_num_voyages, &vyg_price);
 
     //check that user entered voyage
     let num_user_voyages = sea_client.get_u_vygs(&user1_id, &expected_id);
@@ -195,6 +209,11 @@ fn test_end_voyage() {
         &usdc_token_contract_id,
         &vyg_price,
         &max_vygs,
+        &BigInt::from_i64(&e, SCALER),
+        &10,
+        &(10 + 100700),
+        &100,
+        &PriceMode::Fixed,
     );
 
     // enter voyage with user
@@ -207,14 +226,14 @@ fn test_end_voyage() {
     let expected_id: i32 = 1;
     sea_client
         .with_source_account(&user1_acct)
-        .voyage(&expected_id, &user_num_voyages);
+        .voyage(&expected_id, &user_num_voyages, &vyg_price);
 
-    //let voyage expire
+    //let voyage complete
     let expected_expiration: u32 = 10 + 100800;
     e.ledger().set(LedgerInfo {
         timestamp: 100,
         protocol_version: 1,
-        sequence_number: expected_expiration,
+        sequence_number: expected_expiration + 1,
         network_passphrase: Default::default(),
         base_reserve: 10,
     });
@@ -231,3 +250,576 @@ fn test_end_voyage() {
     let remaining_vygs = sea_client.get_u_vygs(&user1_id, &expected_id);
     assert_eq!(remaining_vygs, 0);
 }
+
+#[test]
+fn test_voyage_accumulates() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // embark on the same offering again with user1 and with a second user
+    embark(
+        &e,
+        &setup,
+        &setup.user1_acct,
+        setup.voyage_id,
+        &BigInt::from_i64(&e, 50),
+    );
+    let user3_acct = e.accounts().generate_and_create();
+    let user3_id = Identifier::Account(user3_acct.clone());
+    embark(
+        &e,
+        &setup,
+        &user3_acct,
+        setup.voyage_id,
+        &BigInt::from_i64(&e, 25),
+    );
+
+    //check that the second embark was added to the first
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        setup.user_num_voyages.clone() + BigInt::from_i64(&e, 50)
+    );
+    assert_eq!(
+        setup.sea_client.get_u_vygs(&user3_id, &setup.voyage_id),
+        BigInt::from_i64(&e, 25)
+    );
+    let voyage_info = setup.sea_client.get_voyage(&setup.voyage_id);
+    assert_eq!(voyage_info.n_embarked, BigInt::from_i64(&e, 175));
+    assert!(setup.sea_client.recon_vyg(&setup.voyage_id));
+}
+
+// rewinds the voyage to before positions were tracked, when the offering didn't list its users
+fn untrack_voyage(e: &Env, setup: &VoyageSetup) {
+    e.as_contract(&setup.sea_contract_id, || {
+        e.data().remove(DataKey::VygUsers(setup.voyage_id));
+        e.data().remove(DataKey::VygTrack(setup.voyage_id));
+    });
+}
+
+// rewinds the voyage to before positions accumulated, with user1's position overwritten by their last embark of 50
+fn make_legacy_voyage(e: &Env, setup: &VoyageSetup) {
+    embark(
+        &e,
+        &setup,
+        &setup.user1_acct,
+        setup.voyage_id,
+        &BigInt::from_i64(&e, 50),
+    );
+    let voyage_key = VoyageKey {
+        user_id: setup.user1_id.clone(),
+        voyage_id: setup.voyage_id,
+    };
+    e.as_contract(&setup.sea_contract_id, || {
+        e.data()
+            .set(DataKey::UserVoyage(voyage_key), BigInt::from_i64(&e, 50));
+    });
+    untrack_voyage(&e, &setup);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(34)")]
+fn test_legacy_voyage_needs_fix() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    make_legacy_voyage(&e, &setup);
+
+    // embarking again would compound the damage, so the offering is refused until it's repaired
+    embark(
+        &e,
+        &setup,
+        &setup.user1_acct,
+        setup.voyage_id,
+        &BigInt::from_i64(&e, 10),
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(34)")]
+fn test_legacy_voyage_end_needs_fix() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    untrack_voyage(&e, &setup);
+    set_sequence(&e, 10 + 100800 + 1);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&setup.voyage_id);
+}
+
+#[test]
+fn test_fix_undamaged_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    untrack_voyage(&e, &setup);
+    // the offering doesn't know who is on it, so it can't reconcile until it's repaired
+    assert!(!setup.sea_client.recon_vyg(&setup.voyage_id));
+
+    let mut positions = Map::new(&e);
+    positions.set(setup.user1_id.clone(), setup.user_num_voyages.clone());
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .fix_vyg(&setup.voyage_id, &positions, &BigInt::zero(&e));
+    assert!(setup.sea_client.recon_vyg(&setup.voyage_id));
+}
+
+#[test]
+fn test_fix_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    make_legacy_voyage(&e, &setup);
+    assert!(!setup.sea_client.recon_vyg(&setup.voyage_id));
+
+    // a stray position the offering never counted
+    let user3_id = Identifier::Account(e.accounts().generate_and_create());
+    let voyage_key = VoyageKey {
+        user_id: user3_id.clone(),
+        voyage_id: setup.voyage_id,
+    };
+    e.as_contract(&setup.sea_contract_id, || {
+        e.data()
+            .set(DataKey::UserVoyage(voyage_key), BigInt::from_i64(&e, 10))
+    });
+
+    // captain restores the position from the voyage transaction history, and deletes the stray one
+    let mut positions = Map::new(&e);
+    positions.set(setup.user1_id.clone(), BigInt::from_i64(&e, 150));
+    positions.set(user3_id.clone(), BigInt::zero(&e));
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .fix_vyg(&setup.voyage_id, &positions, &BigInt::zero(&e));
+
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        BigInt::from_i64(&e, 150)
+    );
+    assert_eq!(
+        setup.sea_client.get_u_vygs(&user3_id, &setup.voyage_id),
+        BigInt::zero(&e)
+    );
+    assert!(setup.sea_client.recon_vyg(&setup.voyage_id));
+
+    // the repaired offering can be embarked on again
+    embark(
+        &e,
+        &setup,
+        &setup.user1_acct,
+        setup.voyage_id,
+        &BigInt::from_i64(&e, 10),
+    );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        BigInt::from_i64(&e, 160)
+    );
+    assert!(setup.sea_client.recon_vyg(&setup.voyage_id));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(31)")]
+fn test_fix_voyage_must_reconcile() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    make_legacy_voyage(&e, &setup);
+
+    // positions that don't add up to the voyages embarked on are rejected
+    let mut positions = Map::new(&e);
+    positions.set(setup.user1_id.clone(), BigInt::from_i64(&e, 200));
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .fix_vyg(&setup.voyage_id, &positions, &BigInt::zero(&e));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(32)")]
+fn test_fix_voyage_once() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    make_legacy_voyage(&e, &setup);
+    let mut positions = Map::new(&e);
+    positions.set(setup.user1_id.clone(), BigInt::from_i64(&e, 150));
+    let captain = setup.sea_client.with_source_account(&setup.token_admin);
+    captain.fix_vyg(&setup.voyage_id, &positions, &BigInt::zero(&e));
+
+    // the repaired offering counts its open positions, so it can't be repaired again
+    captain.fix_vyg(&setup.voyage_id, &positions, &BigInt::zero(&e));
+}
+
+#[test]
+fn test_voyage_status() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    let expected_expiration: u32 = 10 + 100800;
+    let status = setup
+        .sea_client
+        .vyg_status(&setup.user1_id, &setup.voyage_id);
+    assert_eq!(status.n_vygs, setup.user_num_voyages);
+    assert_eq!(status.matures, expected_expiration + 1);
+    assert!(!status.mature);
+
+    // voyages sail through the expiration block, so they complete after it
+    set_sequence(&e, expected_expiration);
+    assert!(
+        !setup
+            .sea_client
+            .vyg_status(&setup.user1_id, &setup.voyage_id)
+            .mature
+    );
+    set_sequence(&e, expected_expiration + 1);
+    assert!(
+        setup
+            .sea_client
+            .vyg_status(&setup.user1_id, &setup.voyage_id)
+            .mature
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(33)")]
+fn test_unknown_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup.sea_client.vyg_status(&setup.user1_id, &99);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(24)")]
+fn test_end_voyage_early() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&setup.voyage_id);
+}
+
+#[test]
+fn test_end_voyage_early_penalty() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // captain allows early exits for a 20% penalty
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_exit_p(&BigInt::from_i64(&e, SCALER / 5));
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&setup.voyage_id);
+
+    let expected_shells = setup.user_num_voyages.clone() * BigInt::from_i64(&e, SCALER * 4 / 5);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user1_id),
+        expected_shells
+    );
+    assert_eq!(
+        setup
+            .sea_client
+            .get_u_vygs(&setup.user1_id, &setup.voyage_id),
+        BigInt::zero(&e)
+    );
+}
+
+#[test]
+fn test_scheduled_voyage() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // schedule a voyage that opens in 1000 blocks, takes embarks for 500 and sails for 2000
+    let usdc_token_contract_id = setup.usdc_token_contract_id.clone();
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, SCALER),
+            &1910,
+            &2410,
+            &2000,
+            &PriceMode::Fixed,
+        );
+    let voyage_info = setup.sea_client.get_voyage(&2);
+    assert_eq!(voyage_info.start_ldgr, 1910);
+    assert_eq!(voyage_info.embark_dl, 2410);
+    assert_eq!(voyage_info.sail_dur, 2000);
+    assert_eq!(voyage_info.expiration, 4410);
+    let status = setup.sea_client.vyg_status(&setup.user1_id, &2);
+    assert_eq!(status.matures, 4411);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(20)")]
+fn test_voyage_before_start() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    let usdc_token_contract_id = setup.usdc_token_contract_id.clone();
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, SCALER),
+            &1910,
+            &2410,
+            &2000,
+            &PriceMode::Fixed,
+        );
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .voyage(&2, &BigInt::from_i64(&e, 1), &setup.vyg_price);
+}
+
+#[test]
+#[should_panic(expected = "ContractError(15)")]
+fn test_voyage_start_in_past() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    let usdc_token_contract_id = setup.usdc_token_contract_id.clone();
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, SCALER),
+            &900,
+            &2410,
+            &2000,
+            &PriceMode::Fixed,
+        );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(12)")]
+fn test_voyage_must_sail() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // a voyage embarked on at the deadline would complete in the next block
+    let usdc_token_contract_id = setup.usdc_token_contract_id.clone();
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, SCALER),
+            &910,
+            &2410,
+            &0,
+            &PriceMode::Fixed,
+        );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(15)")]
+fn test_voyage_sails_past_last_block() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // the expiration block would overflow the ledger sequence
+    let usdc_token_contract_id = setup.usdc_token_contract_id.clone();
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, SCALER),
+            &910,
+            &2410,
+            &u32::MAX,
+            &PriceMode::Fixed,
+        );
+}
+
+#[test]
+fn test_legacy_voyage_defaults() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // write a voyage in the layout used before offerings had schedules
+    let usdc_token_contract_id = setup.usdc_token_contract_id.clone();
+    let legacy_voyage = VoyageInfoV1 {
+        vyg_asset: usdc_token_contract_id.clone(),
+        price: setup.vyg_price.clone(),
+        max_vygs: BigInt::from_i64(&e, 1000),
+        n_embarked: BigInt::zero(&e),
+        expiration: 5000,
+    };
+    e.as_contract(&setup.sea_contract_id, || {
+        e.data().set(DataKey::Voyages(1), legacy_voyage)
+    });
+
+    let voyage_info = setup.sea_client.get_voyage(&1);
+    assert_eq!(voyage_info.vyg_asset, usdc_token_contract_id);
+    assert_eq!(voyage_info.expiration, 5000);
+    assert_eq!(voyage_info.start_ldgr, 0);
+    assert_eq!(voyage_info.embark_dl, 5000);
+    assert_eq!(voyage_info.sail_dur, 0);
+    assert!(matches!(voyage_info.price_mode, PriceMode::Fixed));
+}
+
+#[test]
+fn test_voyage_reward() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    // offering that pays 2.5 doubloons per voyage
+    let reward = BigInt::from_i64(&e, SCALER * 5 / 2);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &setup.usdc_token_contract_id,
+            &setup.vyg_price,
+            &BigInt::from_i64(&e, 1000),
+            &reward,
+            &910,
+            &1000,
+            &100,
+            &PriceMode::Fixed,
+        );
+    let num_voyages = BigInt::from_i64(&e, 10);
+    embark(&e, &setup, &setup.user1_acct, 2, &num_voyages);
+    let status = setup.sea_client.vyg_status(&setup.user1_id, &2);
+    assert_eq!(status.payout, num_voyages.clone() * reward.clone());
+
+    set_sequence(&e, 1101);
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .end_voyage(&2);
+    assert_eq!(
+        setup.base_token_client.balance(&setup.user1_id),
+        num_voyages * reward
+    );
+}
+
+// creates a dutch auction offering starting at 100 that can be embarked on until block 1010
+fn new_auction(e: &Env, setup: &VoyageSetup, price_mode: PriceMode) {
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .new_voyage(
+            &setup.usdc_token_contract_id,
+            &BigInt::from_i64(&e, 100),
+            &BigInt::from_i64(&e, 1000),
+            &BigInt::from_i64(&e, SCALER),
+            &910,
+            &1010,
+            &100,
+            &price_mode,
+        );
+}
+
+#[test]
+fn test_linear_auction() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    new_auction(&e, &setup, PriceMode::Linear(BigInt::from_i64(&e, 20)));
+
+    assert_eq!(setup.sea_client.cur_price(&2), BigInt::from_i64(&e, 100));
+    // a quarter of the way through the embark window the price has dropped by a quarter of the premium
+    set_sequence(&e, 935);
+    assert_eq!(setup.sea_client.cur_price(&2), BigInt::from_i64(&e, 80));
+
+    let usdc_spend = BigInt::from_i64(&e, 800);
+    setup
+        .usdc_token_client
+        .with_source_account(&setup.token_admin)
+        .mint(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.user1_id,
+            &usdc_spend,
+        );
+    setup
+        .usdc_token_client
+        .with_source_account(&setup.user1_acct)
+        .approve(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &setup.sea_id,
+            &usdc_spend,
+        );
+    setup
+        .sea_client
+        .with_source_account(&setup.user1_acct)
+        .voyage(&2, &BigInt::from_i64(&e, 10), &BigInt::from_i64(&e, 80));
+    assert_eq!(
+        setup.usdc_token_client.balance(&setup.user1_id),
+        BigInt::zero(&e)
+    );
+    assert_eq!(
+        setup.sea_client.get_u_vygs(&setup.user1_id, &2),
+        BigInt::from_i64(&e, 10)
+    );
+
+    // the reserve price is reached at the embark deadline
+    set_sequence(&e, 1010);
+    assert_eq!(setup.sea_client.cur_price(&2), BigInt::from_i64(&e, 20));
+}
+
+#[test]
+fn test_exponential_auction() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    new_auction(
+        &e,
+        &setup,
+        PriceMode::Expon(ExpDecay {
+            reserve: BigInt::from_i64(&e, 20),
+            half_life: 10,
+        }),
+    );
+
+    assert_eq!(setup.sea_client.cur_price(&2), BigInt::from_i64(&e, 100));
+    // two half lives halve the premium of 80 twice
+    set_sequence(&e, 930);
+    assert_eq!(setup.sea_client.cur_price(&2), BigInt::from_i64(&e, 40));
+    // half way through the next half life, a quarter of the premium of 20 is gone
+    set_sequence(&e, 935);
+    assert_eq!(setup.sea_client.cur_price(&2), BigInt::from_i64(&e, 35));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(23)")]
+fn test_auction_max_price() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    new_auction(&e, &setup, PriceMode::Linear(BigInt::from_i64(&e, 20)));
+
+    // embarks with a max price of 10, the auction is still at 100
+    embark(&e, &setup, &setup.user1_acct, 2, &BigInt::from_i64(&e, 10));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(14)")]
+fn test_auction_reserve_above_price() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    new_auction(&e, &setup, PriceMode::Linear(BigInt::from_i64(&e, 200)));
+}
@@ -0,0 +1,128 @@
+#![cfg(test)]
+
+use soroban_auth::Signature;
+use soroban_bag::token;
+use soroban_sdk::{BigInt, Env};
+mod helper;
+use helper::{
+    back_rewards, create_wrapped_token_contract, generate_contract_id, set_sequence, setup_voyage,
+    VoyageSetup, SCALER,
+};
+extern crate std;
+
+// deploys wsDOUBLOON with the sea as admin, backs the rebase and buries all of user2's doubloons
+fn setup_wrapped(e: &Env, setup: &VoyageSetup) -> token::Client {
+    back_rewards(&e, &setup);
+    let wrapped_token_contract_id = generate_contract_id(&e);
+    let wrapped_token_client =
+        create_wrapped_token_contract(&e, &wrapped_token_contract_id, &setup.token_admin);
+    wrapped_token_client
+        .with_source_account(&setup.token_admin)
+        .set_admin(&Signature::Invoker, &BigInt::zero(&e), &setup.sea_id);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_wrap(&wrapped_token_contract_id);
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .bury(&BigInt::from_i64(&e, SCALER));
+    wrapped_token_client
+}
+
+#[test]
+fn test_wrap() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let wrapped_token_client = setup_wrapped(&e, &setup);
+
+    let amount = BigInt::from_i64(&e, 5000000);
+    assert_eq!(
+        setup.sea_client.wrap_prvw(&amount),
+        BigInt::from_i64(&e, 4999977)
+    );
+    let wrapped = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .wrap(&amount);
+
+    // one wrapped token is minted per buried share
+    assert_eq!(wrapped, BigInt::from_i64(&e, 4999977));
+    assert_eq!(wrapped_token_client.balance(&setup.user2_id), wrapped);
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::from_i64(&e, 4999978)
+    );
+
+    // the wrapped balance stays fixed as the index grows
+    set_sequence(&e, 1910);
+    assert_eq!(wrapped_token_client.balance(&setup.user2_id), wrapped);
+}
+
+#[test]
+fn test_unwrap_preserves_value_across_rate_change() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    let wrapped_token_client = setup_wrapped(&e, &setup);
+
+    let wrapped = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .wrap(&BigInt::from_i64(&e, 5000000));
+
+    // the rate goes up tenfold half way through
+    set_sequence(&e, 1410);
+    setup
+        .sea_client
+        .with_source_account(&setup.token_admin)
+        .set_rate(&BigInt::from_i64(&e, 50));
+    set_sequence(&e, 1910);
+
+    // the wrapped tokens earned the rebase at both rates
+    let expected = BigInt::from_i64(&e, 5000137);
+    assert_eq!(setup.sea_client.unwrp_prvw(&wrapped), expected);
+    let unwrapped = setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .unwrap(&wrapped);
+    assert_eq!(unwrapped, expected);
+    assert_eq!(
+        wrapped_token_client.balance(&setup.user2_id),
+        BigInt::zero(&e)
+    );
+
+    // user2 is back to their original shares, worth the same as if they had never wrapped
+    assert_eq!(
+        setup.sea_client.get_buried(&setup.user2_id),
+        BigInt::from_i64(&e, 9999955)
+    );
+    assert_eq!(
+        setup.sea_client.balance(&setup.user2_id),
+        BigInt::from_i64(&e, 10000275)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ContractError(40)")]
+fn test_wrap_over_balance() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+    setup_wrapped(&e, &setup);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .wrap(&BigInt::from_i64(&e, 2 * SCALER));
+}
+
+#[test]
+#[should_panic(expected = "ContractError(47)")]
+fn test_wrap_without_token() {
+    let e = Env::default();
+    let setup = setup_voyage(&e);
+
+    setup
+        .sea_client
+        .with_source_account(&setup.user2_acct)
+        .wrap(&BigInt::from_i64(&e, SCALER));
+}